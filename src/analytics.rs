@@ -0,0 +1,166 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use wordle::feedback::compute_state;
+use wordle::language;
+use wordle::solver::{filter_words, solver_guesses};
+use crate::Game;
+
+///Statistics over the played games of a state file
+#[derive(Default)]
+pub struct Analytics {
+    pub games: usize,
+    ///Times each letter is guessed at each position
+    pub letters: BTreeMap<char, [usize; 5]>,
+    ///Guesses after the first of each game
+    pub later_guesses: usize,
+    ///Later guesses with a letter already known to be absent
+    pub reusing_guesses: usize,
+    ///Letters already known to be absent, counted at each position they are guessed
+    pub reused_letters: usize,
+    ///Games reaching each guess, total candidates left after it for the player and for the solver
+    pub candidates: [(usize, usize, usize, usize); 6],
+    ///Guesses of each game for the player and the solver, 7 for a lost game
+    pub attempts: Vec<(usize, usize)>,
+}
+
+///Candidates left after each guess of one game, at most 6
+fn candidates_left(guesses: &[String], answer: &str, acceptable_words: &BTreeSet<String>) -> Vec<usize> {
+    let mut candidates = acceptable_words.clone();
+    let mut result = Vec::new();
+    for guess in guesses.iter().take(6) {
+        candidates = filter_words(&candidates, guess, &compute_state(guess, answer));
+        result.push(candidates.len());
+    }
+    result
+}
+
+///Guesses used, 7 if the answer is never guessed
+fn attempts(guesses: &[String], answer: &str) -> usize {
+    match guesses.iter().position(|x| x == answer) {
+        Some(x) => x + 1,
+        None => 7,
+    }
+}
+
+///Analyse every game with guesses, the solver starting with opener on the same answers
+pub fn analyse(games: &[Game], acceptable_words: &BTreeSet<String>, opener: &str) -> Analytics {
+    let mut result = Analytics::default();
+    for game in games.iter().filter(|x| !x.guesses.is_empty()) {
+        let answer = game.answer.to_lowercase();
+        let guesses: Vec<String> = game.guesses.iter().map(|x| x.to_lowercase()).collect();
+        result.games += 1;
+        //已知不在答案中的字母
+        let mut absent: HashSet<char> = HashSet::new();
+        for (index, guess) in guesses.iter().enumerate() {
+            for (i, c) in guess.chars().enumerate().take(5) {
+                result.letters.entry(c).or_insert([0; 5])[i] += 1;
+            }
+            if index > 0 {
+                let reused = guess.chars().filter(|c| absent.contains(c)).count();
+                result.later_guesses += 1;
+                result.reused_letters += reused;
+                if reused > 0 {
+                    result.reusing_guesses += 1;
+                }
+            }
+            absent.extend(guess.chars().filter(|c| !answer.contains(*c)));
+        }
+        let solver = solver_guesses(&answer, acceptable_words, opener);
+        let player_left = candidates_left(&guesses, &answer, acceptable_words);
+        let solver_left = candidates_left(&solver, &answer, acceptable_words);
+        for (i, x) in player_left.iter().enumerate() {
+            result.candidates[i].0 += 1;
+            result.candidates[i].1 += x;
+        }
+        for (i, x) in solver_left.iter().enumerate() {
+            result.candidates[i].2 += 1;
+            result.candidates[i].3 += x;
+        }
+        result.attempts.push((attempts(&guesses, &answer), attempts(&solver, &answer)));
+    }
+    result
+}
+
+///Average of a total, 0 when nothing is counted
+fn average(total: usize, count: usize) -> f64 {
+    if count == 0 { 0.0 } else { total as f64 / count as f64 }
+}
+
+///Average shown in a table, `-` when nothing is counted
+fn average_cell(total: usize, count: usize) -> String {
+    if count == 0 { "-".to_string() } else { format!("{:.2}", average(total, count)) }
+}
+
+impl Analytics {
+    ///Letters by how often they are guessed, the most used first
+    fn letters_by_use(&self) -> Vec<(char, [usize; 5], usize)> {
+        let mut result: Vec<(char, [usize; 5], usize)> = self.letters.iter()
+            .map(|(c, x)| (*c, *x, x.iter().sum()))
+            .collect();
+        result.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        result
+    }
+
+    ///Games the player needs fewer, as many and more guesses than the solver
+    fn comparison(&self) -> (usize, usize, usize) {
+        let better = self.attempts.iter().filter(|x| x.0 < x.1).count();
+        let same = self.attempts.iter().filter(|x| x.0 == x.1).count();
+        (better, same, self.attempts.len() - better - same)
+    }
+
+    ///Text tables of every statistic
+    pub fn text(&self) -> String {
+        let mut text = format!("Games analysed: {}\n\n", self.games);
+        text += "Letter usage by position\nLetter     1     2     3     4     5 Total\n";
+        for (c, counts, total) in self.letters_by_use() {
+            text += &format!("{:<6}", language::upper_char(c));
+            for x in counts {
+                text += &format!(" {:>5}", x);
+            }
+            text += &format!(" {:>5}\n", total);
+        }
+        text += &format!("\nGrey letters reused: {} letter(s) in {} of {} later guess(es)\n",
+            self.reused_letters, self.reusing_guesses, self.later_guesses);
+        text += "\nCandidates left after each guess, games reaching it in brackets\nGuess      Player      Solver\n";
+        for (i, (games, player, solver_games, solver)) in self.candidates.iter().enumerate() {
+            if *games == 0 && *solver_games == 0 {
+                continue;
+            }
+            text += &format!("{:>5} {:>7} ({}) {:>7} ({})\n", i + 1,
+                average_cell(*player, *games), games, average_cell(*solver, *solver_games), solver_games);
+        }
+        let (better, same, worse) = self.comparison();
+        text += &format!("\nGuesses per game, 7 for a lost game: player {:.2}, solver {:.2}\n",
+            average(self.attempts.iter().map(|x| x.0).sum(), self.attempts.len()),
+            average(self.attempts.iter().map(|x| x.1).sum(), self.attempts.len()));
+        text += &format!("Against the solver: {} better, {} same, {} worse\n", better, same, worse);
+        text
+    }
+
+    ///Every statistic as csv rows of table, row, column and value
+    pub fn csv(&self) -> String {
+        let mut text = String::from("table,row,column,value\n");
+        text += &format!("games,all,count,{}\n", self.games);
+        for (c, counts, total) in self.letters_by_use() {
+            for (i, x) in counts.iter().enumerate() {
+                text += &format!("letters,{},{},{}\n", language::upper_char(c), i + 1, x);
+            }
+            text += &format!("letters,{},total,{}\n", language::upper_char(c), total);
+        }
+        text += &format!("grey_reuse,all,later_guesses,{}\n", self.later_guesses);
+        text += &format!("grey_reuse,all,reusing_guesses,{}\n", self.reusing_guesses);
+        text += &format!("grey_reuse,all,reused_letters,{}\n", self.reused_letters);
+        for (i, (games, player, solver_games, solver)) in self.candidates.iter().enumerate() {
+            if *games > 0 {
+                text += &format!("candidates,{},player,{:.4}\n", i + 1, average(*player, *games));
+            }
+            if *solver_games > 0 {
+                text += &format!("candidates,{},solver,{:.4}\n", i + 1, average(*solver, *solver_games));
+            }
+        }
+        let (better, same, worse) = self.comparison();
+        text += &format!("solver,all,player_average,{:.4}\n", average(self.attempts.iter().map(|x| x.0).sum(), self.attempts.len()));
+        text += &format!("solver,all,solver_average,{:.4}\n", average(self.attempts.iter().map(|x| x.1).sum(), self.attempts.len()));
+        text += &format!("solver,all,better,{}\nsolver,all,same,{}\nsolver,all,worse,{}\n", better, same, worse);
+        text
+    }
+}
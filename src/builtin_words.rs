@@ -1532,3 +1532,37 @@ pub const ACCEPTABLE: &[&str] = &[
     "zowee", "zowie", "zulus", "zupan", "zupas", "zuppa", "zurfs", "zuzim", "zygal", "zygon",
     "zymes", "zymic",
 ];
+
+///Answers taken out of the final set after the 2022 edits
+pub const REMOVED_2022: &[&str] = &[
+    "agora", "fibre", "lynch", "pupal", "slave", "wench",
+];
+
+///Common easy words for the kids pack
+pub const KIDS: &[&str] = &[
+    "about", "after", "again", "angel", "apple", "beach", "bench", "berry", "bread", "brown",
+    "brush", "bunch", "bunny", "camel", "candy", "chair", "chalk", "cheer", "child", "clean",
+    "climb", "clock", "cloth", "cloud", "color", "crown", "dance", "dizzy", "dream", "drink",
+    "eagle", "earth", "fairy", "field", "float", "fruit", "funny", "ghost", "giant", "glass",
+    "goose", "grass", "green", "happy", "heart", "heavy", "honey", "horse", "house", "jelly",
+    "juice", "kitty", "koala", "laugh", "lemon", "light", "llama", "lucky", "magic", "melon",
+    "money", "mouse", "music", "night", "noisy", "nurse", "ocean", "otter", "owner", "paint",
+    "paper", "party", "peach", "piano", "piece", "pilot", "pizza", "plane", "plant", "plate",
+    "puppy", "queen", "quiet", "radio", "river", "robot", "round", "scarf", "sheep", "shell",
+    "shirt", "skate", "skirt", "sleep", "smell", "smile", "snack", "snake", "space", "spoon",
+    "sport", "stone", "storm", "story", "sugar", "sunny", "sweet", "swing", "table", "teeth",
+    "thumb", "tiger", "toast", "tooth", "tower", "train", "truck", "tulip", "uncle", "under",
+    "watch", "water", "whale", "wheel", "white", "world", "write", "young", "zebra",
+];
+
+///Food words for the themed pack
+pub const FOOD: &[&str] = &[
+    "apple", "bacon", "bagel", "basil", "berry", "bread", "broth", "candy", "caper", "cater",
+    "chard", "chili", "cider", "cocoa", "cream", "crepe", "cumin", "curry", "diner", "donut",
+    "dough", "feast", "flour", "froth", "fruit", "fudge", "gourd", "grain", "grape", "gravy",
+    "grill", "guava", "honey", "icing", "jelly", "juice", "kebab", "knife", "latte", "lemon",
+    "lunch", "mango", "maple", "melon", "mince", "mocha", "olive", "onion", "pasta", "peach",
+    "pecan", "pesto", "pizza", "plate", "prune", "punch", "ramen", "roast", "salad", "salsa",
+    "sauce", "scone", "shake", "snack", "spice", "spoon", "steak", "steam", "sugar", "sushi",
+    "sweet", "syrup", "thyme", "toast", "treat", "wafer", "wheat", "yeast",
+];
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::difficulty::Difficulty;
+use crate::theme::Theme;
+use crate::ui::Ui;
+use wordle::variant::Variant;
+
+///Keys of a config file, json or toml, unknown keys are rejected
+#[derive(Deserialize, Serialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    pub word: Option<String>,
+    pub random: Option<bool>,
+    pub day: Option<usize>,
+    pub seed: Option<u64>,
+    pub difficult: Option<bool>,
+    pub ultra: Option<bool>,
+    pub stats: Option<bool>,
+    #[serde(alias = "finalset")]
+    pub final_set: Option<String>,
+    #[serde(alias = "acceptableset")]
+    pub acceptable_set: Option<String>,
+    pub state: Option<String>,
+    pub language: Option<String>,
+    pub pack: Option<String>,
+    pub difficulty: Option<Difficulty>,
+    pub time_limit: Option<u64>,
+    pub guess_time_limit: Option<u64>,
+    pub speedrun: Option<NonZeroU64>,
+    pub repair: Option<bool>,
+    pub explain: Option<bool>,
+    pub theme: Option<Theme>,
+    pub ui: Option<Ui>,
+    pub acceptable_weight: Option<f64>,
+    pub frequency: Option<String>,
+    pub variant: Option<Variant>,
+}
+
+///Every key in the order shown by `config show`
+pub const KEYS: [&str; 23] = [
+    "word", "random", "day", "seed", "difficult", "ultra", "stats", "final_set", "acceptable_set",
+    "state", "language", "pack", "difficulty", "time_limit", "guess_time_limit", "speedrun", "repair", "explain",
+    "theme", "ui", "acceptable_weight", "frequency", "variant",
+];
+
+///Old names of keys still accepted
+const ALIASES: [&str; 2] = ["finalset", "acceptableset"];
+
+///Prefix of the environment variables, e.g. WORDLE_SEED
+///Variables with the prefix that name no key belong to something else and are ignored
+pub const ENV_PREFIX: &str = "WORDLE_";
+
+///Where an effective value came from, later ones win
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    UserFile(PathBuf),
+    ProjectFile(PathBuf),
+    ConfigFile(PathBuf),
+    Env(String),
+    CommandLine,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::UserFile(x) => write!(f, "user config {}", x.display()),
+            Source::ProjectFile(x) => write!(f, "project file {}", x.display()),
+            Source::ConfigFile(x) => write!(f, "--config {}", x.display()),
+            Source::Env(x) => write!(f, "env {}", x),
+            Source::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+///Config merged from every layer below the command line
+#[derive(Default)]
+pub struct Config {
+    pub values: ConfigFile,
+    sources: HashMap<String, Source>,
+}
+
+impl Config {
+    ///Merge the user config dir, the project file, the --config file and the environment
+    pub fn load(config: Option<&str>) -> Result<Self, String> {
+        let mut layers: Vec<(Map<String, Value>, Source)> = Vec::new();
+        if let Some(path) = user_dir().and_then(|x| find_file(&x, "config")) {
+            layers.push((read_file(&path)?, Source::UserFile(path)));
+        }
+        if let Some(path) = find_file(Path::new("."), "wordle") {
+            layers.push((read_file(&path)?, Source::ProjectFile(path)));
+        }
+        if let Some(path) = config {
+            let path = PathBuf::from(path);
+            layers.push((read_file(&path)?, Source::ConfigFile(path)));
+        }
+        let mut vars: Vec<(String, String)> = std::env::vars()
+            .filter(|(x, _)| env_key(x).is_some())
+            .collect();
+        vars.sort();
+        for (name, raw) in vars {
+            layers.push((read_env(&name, &raw)?, Source::Env(name)));
+        }
+        let mut merged = Map::new();
+        let mut sources = HashMap::new();
+        for (layer, source) in layers {
+            for (key, value) in layer {
+                sources.insert(key.clone(), source.clone());
+                merged.insert(key, value);
+            }
+        }
+        let values = serde_json::from_value(Value::Object(merged)).map_err(|e| e.to_string())?;
+        Ok(Config { values, sources })
+    }
+
+    ///The layer a key was last set by
+    pub fn source(&self, key: &str) -> Source {
+        self.sources.get(key).cloned().unwrap_or(Source::Default)
+    }
+}
+
+///$XDG_CONFIG_HOME/wordle, or ~/.config/wordle
+fn user_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME").filter(|x| !x.is_empty()) {
+        Some(x) => PathBuf::from(x),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("wordle"))
+}
+
+///<stem>.toml or <stem>.json in a directory, toml first
+fn find_file(dir: &Path, stem: &str) -> Option<PathBuf> {
+    ["toml", "json"].iter()
+        .map(|x| dir.join(format!("{}.{}", stem, x)))
+        .find(|x| x.is_file())
+}
+
+///The keys set by one file, toml if the extension says so and json otherwise
+fn read_file(path: &Path) -> Result<Map<String, Value>, String> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read config {}, {}", path.display(), e))?;
+    let parsed: Result<ConfigFile, String> = if path.extension().is_some_and(|x| x == "toml") {
+        toml::from_str(&data).map_err(|e| e.to_string())
+    }
+    else {
+        serde_json::from_str(&data).map_err(|e| e.to_string())
+    };
+    parsed.map(set_keys).map_err(|e| format!("Invalid config {}, {}", path.display(), e))
+}
+
+///The key named by an environment variable, None unless it is a known key with the prefix
+fn env_key(name: &str) -> Option<String> {
+    let key = name.strip_prefix(ENV_PREFIX)?.to_lowercase();
+    if KEYS.contains(&key.as_str()) || ALIASES.contains(&key.as_str()) { Some(key) } else { None }
+}
+
+///The key set by one environment variable
+///The value is taken as a string first, then as json for numbers and booleans
+fn read_env(name: &str, raw: &str) -> Result<Map<String, Value>, String> {
+    let key = name[ENV_PREFIX.len()..].to_lowercase();
+    let mut layer = Map::new();
+    layer.insert(key.clone(), Value::String(raw.to_string()));
+    if let Ok(x) = serde_json::from_value::<ConfigFile>(Value::Object(layer.clone())) {
+        return Ok(set_keys(x));
+    }
+    let value = serde_json::from_str(raw.trim()).unwrap_or(Value::String(raw.to_string()));
+    layer.insert(key, value);
+    serde_json::from_value::<ConfigFile>(Value::Object(layer))
+        .map(set_keys)
+        .map_err(|e| format!("Invalid environment variable {}, {}", name, e))
+}
+
+///Keys with a value, aliases already resolved
+fn set_keys(config: ConfigFile) -> Map<String, Value> {
+    match serde_json::to_value(config) {
+        Ok(Value::Object(x)) => x.into_iter().filter(|(_, v)| !v.is_null()).collect(),
+        _ => Map::new(),
+    }
+}
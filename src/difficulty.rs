@@ -0,0 +1,86 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use wordle::solver;
+
+///Difficulty band of an answer
+#[derive(clap::ValueEnum, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+///How hard one answer is to find
+pub struct Rating {
+    pub word: String,
+    ///Frequency of the word relative to the most frequent one in the table, 0~1
+    ///None without a frequency table
+    pub frequency: Option<f64>,
+    ///Letters used more than once
+    pub repeated: usize,
+    ///Guesses the solver takes to find the word from its opening guess, 7 if it is not found in 6
+    pub solver_guesses: usize,
+    pub score: f64,
+    pub difficulty: Difficulty,
+}
+
+///Rate every final word, the easiest first
+///The score grows with the solver's guesses, the repeated letters and the rarity of the word
+///Without a frequency table only the guesses and the repeated letters count
+///Answers are split into three equal bands by score
+pub fn rate_words(final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>, opener: &str,
+                  frequency: Option<&HashMap<String, f64>>) -> Vec<Rating> {
+    //与求解器的权重一致：不在表中的词取表中最低的频率
+    let most = frequency.and_then(|x| x.values().copied().reduce(f64::max)).unwrap_or(0.0);
+    let least = frequency.and_then(|x| x.values().copied().reduce(f64::min)).unwrap_or(0.0);
+    let games = solver::play_out_all(final_words, acceptable_words, opener);
+    let mut result: Vec<Rating> = Vec::new();
+    for word in final_words {
+        let frequency = frequency.map(|x| if most > 0.0 { x.get(word).copied().unwrap_or(least) / most } else { 0.0 });
+        let distinct: HashSet<char> = word.chars().collect();
+        let repeated = 5 - distinct.len();
+        let solver_guesses = games.get(word).copied().flatten().unwrap_or(7);
+        //频率按对数计，常见词之间差别不大
+        let rarity = frequency.map_or(0.0, |x| 1.0 - (1.0 + 999.0 * x).log10() / 3.0);
+        let score = solver_guesses as f64 + repeated as f64 + 2.0 * rarity;
+        result.push(Rating {
+            word: word.clone(),
+            frequency,
+            repeated,
+            solver_guesses,
+            score,
+            difficulty: Difficulty::Medium,
+        });
+    }
+    result.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap().then(a.word.cmp(&b.word)));
+    let total = result.len();
+    for (i, rating) in result.iter_mut().enumerate() {
+        rating.difficulty = match i * 3 / total {
+            0 => Difficulty::Easy,
+            1 => Difficulty::Medium,
+            _ => Difficulty::Hard,
+        };
+    }
+    result
+}
+
+///Final words rated in the band
+pub fn words_in_band(ratings: &[Rating], difficulty: Difficulty) -> BTreeSet<String> {
+    ratings
+        .iter()
+        .filter(|x| x.difficulty == difficulty)
+        .map(|x| x.word.clone())
+        .collect()
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::fmt;
+use serde::Serialize;
+
+///Why one letter of a guess got its color
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Reason {
+    ///The answer has the same letter at this position
+    Exact,
+    ///The answer has a copy of the letter elsewhere that is not taken yet
+    Present { in_answer: usize, taken: usize },
+    ///The letter is not in the answer
+    Absent,
+    ///Every copy in the answer is taken by greens or earlier letters of the guess
+    Exhausted { in_answer: usize },
+}
+
+///Feedback of one letter of a guess
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct LetterFeedback {
+    ///Position starting from 0
+    pub position: usize,
+    pub letter: char,
+    ///2->Green 1->Yellow 0->Red
+    pub state: i32,
+    pub reason: Reason,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reason::Exact => write!(f, "in the answer at this position"),
+            Reason::Present { in_answer, taken } =>
+                write!(f, "in the answer elsewhere, {} of {} not taken yet", in_answer - taken, in_answer),
+            Reason::Absent => write!(f, "not in the answer"),
+            Reason::Exhausted { in_answer } =>
+                write!(f, "only {} in the answer, taken by greens or earlier letters", in_answer),
+        }
+    }
+}
+
+///Explain the color of each letter of a guess
+///Greens take their copies of a letter first, then the other letters from left to right
+pub fn explain(guess: &str, answer: &str) -> Vec<LetterFeedback> {
+    let guess: Vec<char> = guess.chars().collect();
+    let answer: Vec<char> = answer.chars().collect();
+    let mut answer_map: HashMap<char, usize> = HashMap::new();
+    for c in &answer {
+        *answer_map.entry(*c).or_insert(0) += 1;
+    }
+    //每个字母已被占用的个数
+    let mut taken: HashMap<char, usize> = HashMap::new();
+    let mut result: Vec<LetterFeedback> = Vec::new();
+    for (i, letter) in guess.iter().enumerate() {
+        let exact = answer.get(i) == Some(letter);
+        if exact {
+            *taken.entry(*letter).or_insert(0) += 1;
+        }
+        result.push(LetterFeedback {
+            position: i,
+            letter: *letter,
+            state: if exact { 2 } else { 0 },
+            reason: if exact { Reason::Exact } else { Reason::Absent },
+        });
+    }
+    for x in result.iter_mut().filter(|x| x.state != 2) {
+        let in_answer = answer_map.get(&x.letter).copied().unwrap_or(0);
+        if in_answer == 0 {
+            continue;
+        }
+        let used = taken.entry(x.letter).or_insert(0);
+        if *used < in_answer {
+            x.state = 1;
+            x.reason = Reason::Present { in_answer, taken: *used };
+            *used += 1;
+        }
+        else {
+            x.reason = Reason::Exhausted { in_answer };
+        }
+    }
+    result
+}
+
+///State of each letter of a guess, 2->Green 1->Yellow 0->Red
+///Same colors as `explain` without allocating, the solvers call it for every pair of words
+pub fn compute_state(guess: &str, answer: &str) -> [i32; 5] {
+    let mut guess_letters = [None; 5];
+    let mut answer_letters = [None; 5];
+    for (i, c) in guess.chars().take(5).enumerate() {
+        guess_letters[i] = Some(c);
+    }
+    for (i, c) in answer.chars().take(5).enumerate() {
+        answer_letters[i] = Some(c);
+    }
+    let mut state = [0; 5];
+    for i in 0..5 {
+        if guess_letters[i].is_some() && guess_letters[i] == answer_letters[i] {
+            state[i] = 2;
+            answer_letters[i] = None;
+        }
+    }
+    //其余字母从左到右占用答案中未被占用的同一字母
+    for i in 0..5 {
+        if state[i] == 2 || guess_letters[i].is_none() {
+            continue;
+        }
+        if let Some(j) = (0..5).find(|j| answer_letters[*j] == guess_letters[i]) {
+            state[i] = 1;
+            answer_letters[j] = None;
+        }
+    }
+    state
+}
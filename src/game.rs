@@ -0,0 +1,463 @@
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use std::io::{self, Write};
+use std::time::Instant;
+use serde_json::{json, Value};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::feedback::{self, compute_state};
+use crate::hard_mode::{self, HardMode};
+use crate::hints::{self, HintLevel, Hints};
+use crate::input::{InputError, LineInput};
+use crate::language::{self, Language};
+use crate::solver::{filter_words, information_entrophy, weighted_guess_scores, Weights, WordEntrophy};
+use crate::theme::Theme;
+use crate::timer::{Clock, Timer};
+use crate::variant::Variant;
+
+///How a round talks to the player
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    ///Prompts, colors and suggestions for a person at a terminal
+    Normal,
+    ///The normal mode redrawing the whole board after each guess
+    Tui,
+    ///One line per guess in the format of the course tests
+    Test,
+    ///One json object per line for each event, input as in the test mode
+    Jsonl,
+}
+
+///Everything a round reads from and writes to
+pub struct Io<'a> {
+    pub input: &'a mut dyn LineInput,
+    pub output: &'a mut dyn Write,
+    ///Messages besides the game, e.g. why a difficult guess is rejected in test mode
+    pub error: &'a mut dyn Write,
+    pub clock: &'a dyn Clock,
+}
+
+///Rules of one round
+pub struct Rules<'a> {
+    pub mode: Mode,
+    pub language: &'a Language,
+    ///Answers typed by the player must be one of them
+    pub final_words: &'a BTreeSet<String>,
+    pub acceptable_words: &'a BTreeSet<String>,
+    ///Solver ranking for the first guess, only computed when needed
+    pub opening: &'a dyn Fn() -> BinaryHeap<WordEntrophy>,
+    ///Likelihood of each word being the answer for the solver, every candidate alike if None
+    pub weights: Option<&'a Weights>,
+    pub difficult: bool,
+    pub ultra: bool,
+    pub explain: bool,
+    ///Feedback rules, the hints and hard mode need a truthful one
+    pub variant: Variant,
+    pub theme: Theme,
+    ///Seconds allowed for the whole game and for each guess
+    pub time_limit: Option<u64>,
+    pub guess_time_limit: Option<u64>,
+    ///Whether the time of each guess is recorded
+    pub record_times: bool,
+}
+
+///What happened in one round
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub answer: String,
+    pub win: bool,
+    ///Accepted guesses in uppercase
+    pub guesses: Vec<String>,
+    ///2->Green 1->Yellow 0->Red for each accepted guess, as shown in the variant
+    pub states: Vec<[i32; 5]>,
+    ///Milliseconds taken for each guess if recorded
+    pub times: Vec<u64>,
+    pub timed_out: bool,
+    ///Level of each hint used
+    pub hints: Vec<u8>,
+}
+
+///The answer of a day from the final words, days are shuffled by the rng
+pub fn answer_of_day(final_words: &BTreeSet<String>, day: usize, rng: &mut impl Rng) -> String {
+    let mut list: Vec<usize> = (0..final_words.len()).collect();
+    list.shuffle(rng);
+    let index = list[day - 1];
+    final_words.iter().nth(index).cloned().unwrap_or_default()
+}
+
+///Print the keyboard colored by the state of each letter
+pub fn print_keyboard(out: &mut dyn Write, alphabet: &HashMap<char, char>, keyboard: &[String], theme: Theme) -> io::Result<()> {
+    for (i, row) in keyboard.iter().enumerate() {
+        if i != 0 {
+            writeln!(out)?;
+        }
+        for letter in row.chars() {
+            match alphabet.get(&letter) {
+                Some(x) => write!(out, "{}", theme.letter(letter, *x))?,
+                None => write!(out, "{}", " ".repeat(theme.width()))?,
+            }
+        }
+    }
+    Ok(())
+}
+
+///Print why each letter of the guess got its color
+pub fn print_explanation(out: &mut dyn Write, guess: &str, answer: &str, theme: Theme) -> io::Result<()> {
+    for x in feedback::explain(guess, answer) {
+        let color = match x.state {
+            2 => theme.style('G').apply_to("correct"),
+            1 => theme.style('Y').apply_to("present"),
+            _ => theme.style('R').apply_to("absent"),
+        };
+        writeln!(out, "{} {} {}: {}", hard_mode::ordinal(x.position), language::upper_char(x.letter), color, x.reason)?;
+    }
+    Ok(())
+}
+
+///Write one event of the jsonl mode
+fn event(out: &mut dyn Write, value: Value) -> io::Result<()> {
+    writeln!(out, "{}", value)
+}
+
+///Clear the screen and draw every row of the board, empty rows included
+fn draw_board(out: &mut dyn Write, round: &Round, alphabet: &HashMap<char, char>, rules: &Rules) -> io::Result<()> {
+    let theme = rules.theme;
+    write!(out, "\x1b[2J\x1b[H")?;
+    for (guess, state) in round.guesses.iter().zip(&round.states) {
+        print_row(out, &guess.to_lowercase(), state, rules.variant, theme)?;
+        writeln!(out)?;
+    }
+    for _ in round.guesses.len()..6 {
+        writeln!(out, "{}", theme.style('X').apply_to(format!("{:^1$}", "_", theme.width()).repeat(5)))?;
+    }
+    writeln!(out)?;
+    if rules.variant.shows_keyboard() {
+        print_keyboard(out, alphabet, &rules.language.keyboard, theme)?;
+        writeln!(out)?;
+    }
+    writeln!(out)
+}
+
+///Print the letters of a guess colored by the state shown
+///The counts variant prints the letters uncolored followed by the numbers of greens and yellows
+pub fn print_row(out: &mut dyn Write, guess: &str, state: &[i32; 5], variant: Variant, theme: Theme) -> io::Result<()> {
+    if variant == Variant::Counts && state != &[2; 5] {
+        for letter in guess.chars() {
+            write!(out, "{}", theme.letter(letter, 'X'))?;
+        }
+        let greens = state.iter().filter(|x| **x == 2).count();
+        let yellows = state.iter().filter(|x| **x == 1).count();
+        return write!(out, " {} {}", theme.style('G').apply_to(format!("{} correct", greens)),
+            theme.style('Y').apply_to(format!("{} present", yellows)));
+    }
+    for (letter, x) in guess.chars().zip(state) {
+        write!(out, "{}", theme.letter(letter, ['R', 'Y', 'G'][*x as usize]))?;
+    }
+    Ok(())
+}
+
+///Read one line, a line that comes in after the deadline counts as timed out
+fn read_line(io: &mut Io, deadline: Option<Instant>) -> Result<String, InputError> {
+    let line = io.input.read_line(deadline)?;
+    match deadline {
+        Some(x) if io.clock.now() > x => Err(InputError::TimedOut),
+        _ => Ok(line),
+    }
+}
+
+///First letter of the next line that is not blank
+fn read_char(io: &mut Io, deadline: Option<Instant>) -> Result<char, InputError> {
+    loop {
+        if let Some(c) = read_line(io, deadline)?.trim().chars().next() {
+            return Ok(c);
+        }
+    }
+}
+
+fn closed() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "input closed before the answer")
+}
+
+///Ask for the answer, in normal mode until it is one of the final words
+fn read_answer(rules: &Rules, io: &mut Io) -> io::Result<String> {
+    if rules.mode == Mode::Test {
+        return read_line(io, None).map(|x| language::normalize(&x)).map_err(|_| closed());
+    }
+    loop {
+        write!(io.output, "Please input the answer here: ")?;
+        io.output.flush()?;
+        let answer = language::normalize(&read_line(io, None).map_err(|_| closed())?);
+        if rules.final_words.contains(&answer) {
+            return Ok(answer);
+        }
+        writeln!(io.output, "Invalid answer! Please try again!")?;
+    }
+}
+
+///Play one round, the answer is asked for if not assigned
+///The round ends early when the clock runs out or the input is closed
+pub fn play(rules: &Rules, io: &mut Io, answer: Option<&str>) -> io::Result<Round> {
+    let theme = rules.theme;
+    let normal = matches!(rules.mode, Mode::Normal | Mode::Tui);
+    let tui = rules.mode == Mode::Tui;
+    let jsonl = rules.mode == Mode::Jsonl;
+    if normal && rules.difficult {
+        writeln!(io.output, "{}! You choosed {} mode!", theme.bad().apply_to("Warning"), theme.bad().apply_to("DIFFICULT"))?;
+    }
+    if normal && !rules.variant.is_classic() {
+        writeln!(io.output, "Playing the {} variant!", theme.bad().apply_to(rules.variant))?;
+    }
+    let answer = match answer {
+        Some(x) => language::normalize(x),
+        None => read_answer(rules, io)?,
+    };
+    if normal {
+        writeln!(io.output, "Answer assigned: {} :)", language::to_upper(&answer))?;
+    }
+    let mut round = Round { answer: answer.clone(), ..Default::default() };
+    //字母表映射各字符状态
+    let mut alphabet: HashMap<char, char> = HashMap::new();
+    for c in rules.language.letters() {
+        alphabet.insert(c, 'X');
+    }
+    //困难模式使用的判断
+    let mut hard_mode = HardMode::new(rules.ultra);
+    //计时从输入第一次猜测开始
+    let mut timer = Timer::new(io.clock, rules.time_limit, rules.guess_time_limit, rules.record_times);
+    let mut hints = Hints::default();
+    //仍可能是答案的单词，用于提示
+    let mut candidates = rules.acceptable_words.clone();
+    let suggestions = normal && rules.variant.truthful();
+    let mut ranking = if suggestions { (rules.opening)() } else { BinaryHeap::new() };
+    if tui {
+        draw_board(io.output, &round, &alphabet, rules)?;
+    }
+
+    while round.guesses.len() < 6 {
+        if suggestions {
+            write!(io.output, "Do you want any suggestions? {}/{} or a hint {} ",
+                theme.style('Y').bold().apply_to("[Y]"),
+                theme.bad().apply_to("[N]"),
+                theme.info().apply_to("[1-4]"))?;
+            io.output.flush()?;
+            let command = match read_char(io, timer.deadline()) {
+                Ok(x) => x,
+                Err(InputError::TimedOut) => {
+                    round.timed_out = true;
+                    break;
+                },
+                Err(InputError::Closed) => 'N',
+            };
+            //分级提示，等级越高代价越大
+            if let Some(level) = command.to_digit(10).and_then(HintLevel::from_number) {
+                let played: Vec<String> = round.guesses.iter().map(|x| x.to_lowercase()).collect();
+                let hint = give_hint(rules, &mut hints, level, &answer, &played, &candidates);
+                if hint.revealed() {
+                    round.hints.push(level as u8);
+                    writeln!(io.output, "{} {} (penalty {})", theme.info().apply_to("Hint:"), hint, level.penalty())?;
+                }
+                else {
+                    writeln!(io.output, "{} {}", theme.info().apply_to("Hint:"), hint)?;
+                }
+                continue;
+            }
+            if command == 'Y' {
+                writeln!(io.output, "Possible guesses: {} in total", theme.style('Y').bold().apply_to(candidates.len()))?;
+                //加权时排名用的是分数而不是信息熵
+                let measure = if rules.weights.is_some() { "Score" } else { "Information Entrophy" };
+                writeln!(io.output, "{} and their {}:",
+                    theme.good().apply_to("Most possible words"),
+                    theme.bad().apply_to(measure))?;
+                for word in std::iter::from_fn(|| ranking.pop()).take(5) {
+                    writeln!(io.output, "{} {:.4}",
+                        theme.good().apply_to(language::to_upper(&word.0)),
+                        theme.bad().apply_to(word.1 as f64 / 1000000000.0))?;
+                }
+            }
+        }
+        if normal {
+            match timer.seconds_left() {
+                Some(x) => write!(io.output, "Please input your guess here ({}s left): ", x)?,
+                None => write!(io.output, "Please input your guess here: ")?,
+            }
+            io.output.flush()?;
+        }
+        let guess = match read_line(io, timer.deadline()) {
+            Ok(x) => language::normalize(&x),
+            Err(InputError::TimedOut) => {
+                round.timed_out = true;
+                break;
+            },
+            Err(InputError::Closed) => break,
+        };
+        //测试模式请求提示，如 HINT 2
+        if !normal {
+            if let Some(request) = hints::parse_request(&guess) {
+                match request {
+                    Some(_) if !rules.variant.truthful() => {
+                        if jsonl {
+                            event(io.output, json!({"event": "invalid", "input": guess, "reason": format!("no hints in the {} variant", rules.variant)}))?;
+                        }
+                        else {
+                            writeln!(io.output, "INVALID")?;
+                        }
+                    },
+                    Some(level) => {
+                        let played: Vec<String> = round.guesses.iter().map(|x| x.to_lowercase()).collect();
+                        let hint = give_hint(rules, &mut hints, level, &answer, &played, &candidates);
+                        if hint.revealed() {
+                            round.hints.push(level as u8);
+                        }
+                        if jsonl {
+                            event(io.output, json!({"event": "hint", "level": level as u8, "revealed": hint.revealed(), "hint": hint.to_string()}))?;
+                        }
+                        else {
+                            writeln!(io.output, "{}", hint.protocol())?;
+                        }
+                    },
+                    None if jsonl => event(io.output, json!({"event": "invalid", "input": guess, "reason": "unknown hint level"}))?,
+                    None => writeln!(io.output, "INVALID")?,
+                }
+                continue;
+            }
+        }
+        //不合法判断处理
+        if !language::is_five_letters(&guess) || !rules.acceptable_words.contains(&guess) || !guess.chars().all(char::is_alphabetic) {
+            if normal {
+                writeln!(io.output, "Invalid input! :( Please guess again!")?;
+            }
+            else if jsonl {
+                event(io.output, json!({"event": "invalid", "input": guess, "reason": "not an acceptable word"}))?;
+            }
+            else {
+                writeln!(io.output, "INVALID")?;
+            }
+            continue;
+        }
+        if rules.difficult {
+            if let Err(violation) = hard_mode.check(&guess) {
+                if normal {
+                    writeln!(io.output, "Invalid input, {}! :( Please guess again!", theme.bad().apply_to(violation))?;
+                }
+                else if jsonl {
+                    event(io.output, json!({"event": "invalid", "input": guess, "reason": violation.to_string()}))?;
+                }
+                else {
+                    writeln!(io.output, "INVALID")?;
+                    writeln!(io.error, "{}", violation)?;
+                }
+                continue;
+            }
+        }
+        //合法情况
+        let state = compute_state(&guess, &answer);
+        let shown = rules.variant.feedback(&guess, &answer, state);
+        round.guesses.push(language::to_upper(&guess));
+        round.states.push(shown);
+        if let Some(x) = timer.lap() {
+            round.times.push(x);
+        }
+        let win = guess == answer;
+        if normal && win {
+            if tui {
+                draw_board(io.output, &round, &alphabet, rules)?;
+            }
+            else {
+                writeln!(io.output, "{}", answer.chars().map(|c| theme.letter(c, 'G')).collect::<String>())?;
+            }
+            writeln!(io.output, "Correct! :D You tried {} times.", round.guesses.len())?;
+            round.win = true;
+            return Ok(round);
+        }
+        //键盘只按显示的结果更新
+        for (i, letter) in guess.chars().enumerate().filter(|_| rules.variant.shows_keyboard()) {
+            if let Some(x) = alphabet.get_mut(&letter) {
+                match shown[i] {
+                    2 => *x = 'G',
+                    1 if *x != 'G' => *x = 'Y',
+                    0 if *x == 'X' => *x = 'R',
+                    _ => {},
+                }
+            }
+        }
+        hard_mode.update(&guess, &state);
+        candidates = filter_words(&candidates, &guess, &state);
+        let show: String = shown.iter().map(|x| ['R', 'Y', 'G'][*x as usize]).collect();
+        let letters: String = rules.language.letters().iter().filter_map(|c| alphabet.get(c)).collect();
+        if tui {
+            draw_board(io.output, &round, &alphabet, rules)?;
+        }
+        else if normal {
+            print_row(io.output, &guess, &shown, rules.variant, theme)?;
+            writeln!(io.output)?;
+            if rules.variant.shows_keyboard() {
+                print_keyboard(io.output, &alphabet, &rules.language.keyboard, theme)?;
+                writeln!(io.output)?;
+            }
+        }
+        if normal {
+            if rules.explain {
+                print_explanation(io.output, &guess, &answer, theme)?;
+            }
+            //更新信息熵集
+            if suggestions {
+                ranking = rank_candidates(rules, &candidates);
+            }
+        }
+        else if jsonl {
+            let mut value = json!({"event": "guess", "attempt": round.guesses.len(), "guess": language::to_upper(&guess),
+                "state": shown, "feedback": show, "keyboard": letters});
+            if rules.explain {
+                value["explanation"] = json!(feedback::explain(&guess, &answer));
+            }
+            event(io.output, value)?;
+            if win {
+                round.win = true;
+                break;
+            }
+        }
+        else {
+            writeln!(io.output, "{} {}", show, letters)?;
+            if rules.explain && !win {
+                print_explanation(io.output, &guess, &answer, theme)?;
+            }
+            if win {
+                writeln!(io.output, "CORRECT {}", round.guesses.len())?;
+                round.win = true;
+                return Ok(round);
+            }
+        }
+    }
+    if jsonl {
+        event(io.output, json!({"event": "end", "answer": language::to_upper(&answer), "win": round.win,
+            "attempts": round.guesses.len(), "timed_out": round.timed_out, "hints": round.hints}))?;
+    }
+    else if normal {
+        if round.timed_out {
+            writeln!(io.output, "{}", theme.bad().apply_to("Time is up!"))?;
+        }
+        writeln!(io.output, "You failed! :( The correct answer is {}", language::to_upper(&answer))?;
+    }
+    else {
+        if round.timed_out {
+            writeln!(io.output, "TIMEOUT")?;
+        }
+        writeln!(io.output, "FAILED {}", language::to_upper(&answer))?;
+    }
+    Ok(round)
+}
+
+///Solver ranking of the candidates left, weighted if the rules say so
+fn rank_candidates(rules: &Rules, candidates: &BTreeSet<String>) -> BinaryHeap<WordEntrophy> {
+    match rules.weights {
+        Some(x) => weighted_guess_scores(candidates, x),
+        None => information_entrophy(candidates),
+    }
+}
+
+///A hint after the guesses so far, the best guess of the first turn comes from the opening ranking
+fn give_hint(rules: &Rules, hints: &mut Hints, level: HintLevel, answer: &str, played: &[String], candidates: &BTreeSet<String>) -> hints::Hint {
+    hints.give(level, answer, played, candidates, || {
+        let ranking = if played.is_empty() { (rules.opening)() } else { rank_candidates(rules, candidates) };
+        ranking.peek().map(|x| x.0.clone())
+    })
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::language;
+
+///Why a guess is rejected in difficult mode
+#[derive(Debug, PartialEq, Eq)]
+pub enum HardModeViolation {
+    ///A green letter is not kept in place
+    GreenMoved { position: usize, letter: char },
+    ///Fewer copies of a revealed letter than known to be in the answer
+    MissingLetter { letter: char, count: usize },
+    ///More copies of a letter than known to be in the answer, ultra mode only
+    ExtraLetter { letter: char, count: usize },
+    ///A letter at a position already known to be wrong, ultra mode only
+    WrongPosition { position: usize, letter: char },
+}
+
+///English ordinal of a position starting from 0
+pub fn ordinal(position: usize) -> String {
+    let n = position + 1;
+    let suffix = match n {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+impl fmt::Display for HardModeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HardModeViolation::GreenMoved { position, letter } =>
+                write!(f, "{} letter must be {}", ordinal(*position), language::upper_char(*letter)),
+            HardModeViolation::MissingLetter { letter, count: 1 } =>
+                write!(f, "guess must contain {}", language::upper_char(*letter)),
+            HardModeViolation::MissingLetter { letter, count } =>
+                write!(f, "guess must contain {} {}s", count, language::upper_char(*letter)),
+            HardModeViolation::ExtraLetter { letter, count: 0 } =>
+                write!(f, "guess must not contain {}", language::upper_char(*letter)),
+            HardModeViolation::ExtraLetter { letter, count } =>
+                write!(f, "guess must contain at most {} {}s", count, language::upper_char(*letter)),
+            HardModeViolation::WrongPosition { position, letter } =>
+                write!(f, "{} letter must not be {}", ordinal(*position), language::upper_char(*letter)),
+        }
+    }
+}
+
+///Constraints derived from all previous feedback in difficult mode
+///The ultra mode also forbids grey letters and positions known to be wrong
+pub struct HardMode {
+    ultra: bool,
+    greens: [Option<char>; 5],
+    min_counts: HashMap<char, usize>,
+    max_counts: HashMap<char, usize>,
+    wrong_positions: [Vec<char>; 5],
+}
+
+impl HardMode {
+    pub fn new(ultra: bool) -> Self {
+        HardMode {
+            ultra,
+            greens: [None; 5],
+            min_counts: HashMap::new(),
+            max_counts: HashMap::new(),
+            wrong_positions: Default::default(),
+        }
+    }
+
+    ///Learn from the state of one guess, 2->Green 1->Yellow 0->Red
+    pub fn update(&mut self, guess: &str, state: &[i32; 5]) {
+        let mut found: HashMap<char, usize> = HashMap::new();
+        let mut grey: Vec<char> = Vec::new();
+        for (i, letter) in guess.chars().enumerate() {
+            match state[i] {
+                2 => {
+                    self.greens[i] = Some(letter);
+                    *found.entry(letter).or_insert(0) += 1;
+                },
+                1 => {
+                    self.wrong_positions[i].push(letter);
+                    *found.entry(letter).or_insert(0) += 1;
+                },
+                _ => {
+                    self.wrong_positions[i].push(letter);
+                    grey.push(letter);
+                },
+            }
+        }
+        for (letter, count) in &found {
+            let x = self.min_counts.entry(*letter).or_insert(0);
+            *x = (*x).max(*count);
+        }
+        //灰色字母说明答案中该字母的个数已经确定
+        for letter in grey {
+            self.max_counts.insert(letter, found.get(&letter).copied().unwrap_or(0));
+        }
+    }
+
+    ///Check a guess against the constraints, reporting the first violation
+    pub fn check(&self, guess: &str) -> Result<(), HardModeViolation> {
+        let letters: Vec<char> = guess.chars().collect();
+        for (i, green) in self.greens.iter().enumerate() {
+            if let Some(letter) = *green {
+                if letters[i] != letter {
+                    return Err(HardModeViolation::GreenMoved { position: i, letter });
+                }
+            }
+        }
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in &letters {
+            *counts.entry(*c).or_insert(0) += 1;
+        }
+        let mut min_counts: Vec<(&char, &usize)> = self.min_counts.iter().collect();
+        min_counts.sort();
+        for (letter, count) in min_counts {
+            if counts.get(letter).copied().unwrap_or(0) < *count {
+                return Err(HardModeViolation::MissingLetter { letter: *letter, count: *count });
+            }
+        }
+        if !self.ultra {
+            return Ok(());
+        }
+        for (i, green) in self.greens.iter().enumerate() {
+            if green.is_none() && self.wrong_positions[i].contains(&letters[i]) {
+                return Err(HardModeViolation::WrongPosition { position: i, letter: letters[i] });
+            }
+        }
+        let mut max_counts: Vec<(&char, &usize)> = self.max_counts.iter().collect();
+        max_counts.sort();
+        for (letter, count) in max_counts {
+            if counts.get(letter).copied().unwrap_or(0) > *count {
+                return Err(HardModeViolation::ExtraLetter { letter: *letter, count: *count });
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use crate::hard_mode::ordinal;
+use crate::language;
+
+///Graded hints, a higher level tells more and costs more
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HintLevel {
+    ///Number of candidates left
+    Candidates = 1,
+    ///A letter at a position not found yet
+    Position = 2,
+    ///A letter in the answer not found yet
+    Letter = 3,
+    ///The solver's best guess
+    BestGuess = 4,
+}
+
+impl HintLevel {
+    pub fn from_number(n: u32) -> Option<Self> {
+        match n {
+            1 => Some(HintLevel::Candidates),
+            2 => Some(HintLevel::Position),
+            3 => Some(HintLevel::Letter),
+            4 => Some(HintLevel::BestGuess),
+            _ => None,
+        }
+    }
+
+    ///Penalty of the hint, the same as its level
+    pub fn penalty(self) -> u32 {
+        self as u32
+    }
+}
+
+///What one hint tells, None if there is nothing left to reveal
+pub enum Hint {
+    Candidates(usize),
+    Position(Option<(usize, char)>),
+    Letter(Option<char>),
+    BestGuess(Option<String>),
+}
+
+impl Hint {
+    ///Whether anything is revealed, only then the penalty counts
+    pub fn revealed(&self) -> bool {
+        !matches!(self, Hint::Position(None) | Hint::Letter(None) | Hint::BestGuess(None))
+    }
+
+    ///One line for the test mode, e.g. "HINT 2 2 A"
+    pub fn protocol(&self) -> String {
+        match self {
+            Hint::Candidates(x) => format!("HINT 1 {}", x),
+            Hint::Position(Some((i, c))) => format!("HINT 2 {} {}", i + 1, language::upper_char(*c)),
+            Hint::Letter(Some(c)) => format!("HINT 3 {}", language::upper_char(*c)),
+            Hint::BestGuess(Some(x)) => format!("HINT 4 {}", language::to_upper(x)),
+            Hint::Position(None) => "HINT 2 -".to_string(),
+            Hint::Letter(None) => "HINT 3 -".to_string(),
+            Hint::BestGuess(None) => "HINT 4 -".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Hint::Candidates(x) => write!(f, "{} possible word(s) left", x),
+            Hint::Position(Some((i, c))) => write!(f, "the {} letter is {}", ordinal(*i), language::upper_char(*c)),
+            Hint::Letter(Some(c)) => write!(f, "the answer contains {}", language::upper_char(*c)),
+            Hint::BestGuess(Some(x)) => write!(f, "the solver would guess {}", language::to_upper(x)),
+            _ => write!(f, "nothing left to reveal"),
+        }
+    }
+}
+
+///Parse a hint request typed in test mode, e.g. "hint 2"
+///Return None if the line is not a request, e.g. the guess "hints"
+pub fn parse_request(line: &str) -> Option<Option<HintLevel>> {
+    let rest = line.strip_prefix("hint ")?;
+    Some(rest.trim().parse::<u32>().ok().and_then(HintLevel::from_number))
+}
+
+///Letters and positions revealed by hints in one game
+#[derive(Default)]
+pub struct Hints {
+    letters: Vec<char>,
+    positions: Vec<usize>,
+}
+
+impl Hints {
+    ///Give a hint about the answer after the guesses so far
+    ///The best guess is only computed when asked for
+    pub fn give(&mut self, level: HintLevel, answer: &str, guesses: &[String],
+                candidates: &BTreeSet<String>, best_guess: impl FnOnce() -> Option<String>) -> Hint {
+        let letters: Vec<char> = answer.chars().collect();
+        match level {
+            HintLevel::Candidates => Hint::Candidates(candidates.len()),
+            HintLevel::Position => {
+                let found = (0..letters.len()).find(|i| {
+                    !self.positions.contains(i) && !guesses.iter().any(|g| g.chars().nth(*i) == Some(letters[*i]))
+                });
+                if let Some(i) = found {
+                    self.positions.push(i);
+                    self.letters.push(letters[i]);
+                }
+                Hint::Position(found.map(|i| (i, letters[i])))
+            },
+            HintLevel::Letter => {
+                //已经在猜测中出现过的字母不再提示
+                let found = letters.iter().find(|c| {
+                    !self.letters.contains(c) && !guesses.iter().any(|g| g.contains(**c))
+                });
+                if let Some(c) = found {
+                    self.letters.push(*c);
+                }
+                Hint::Letter(found.copied())
+            },
+            HintLevel::BestGuess => Hint::BestGuess(best_guess()),
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeSet;
+use serde::{Deserialize, Serialize};
+use wordle::feedback::compute_state;
+use wordle::solver::filter_words;
+use wordle::variant::Variant;
+use crate::Game;
+
+///Formats of an exported history
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    ///One json object per line, the format `import` reads
+    Ndjson,
+}
+
+///One guess of a stored game, a row of the export
+///The fields of the game are repeated on each of its rows
+///A game without guesses has a single row with guess number 0, an empty guess and feedback
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GuessRow {
+    ///Index of the game in the state file starting from 1
+    pub game: usize,
+    pub answer: String,
+    ///Starting from 1, 0 in the row of a game without guesses
+    pub guess_number: usize,
+    pub guess: String,
+    ///G correct, Y present and R absent, e.g. GYRRR, the true feedback whatever the variant
+    pub feedback: String,
+    ///Acceptable words still possible after the guess
+    pub candidates: usize,
+    pub difficult: bool,
+    ///Milliseconds taken for the guess, only in timed games
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_ms: Option<u64>,
+    ///Played in the practice mode
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub practice: bool,
+    ///Feedback rules of the game
+    #[serde(default, skip_serializing_if = "Variant::is_classic")]
+    pub variant: Variant,
+    ///Day of the answer in random mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day: Option<usize>,
+    ///The game ran out of time
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    ///Level of each hint used in the game
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<u8>,
+}
+
+impl GuessRow {
+    ///Row of a game with the guess left empty
+    fn of_game(index: usize, game: &Game, candidates: usize) -> GuessRow {
+        GuessRow {
+            game: index + 1,
+            answer: game.answer.clone(),
+            guess_number: 0,
+            guess: String::new(),
+            feedback: String::new(),
+            candidates,
+            difficult: game.difficult,
+            time_ms: None,
+            practice: game.practice,
+            variant: game.variant,
+            day: game.day,
+            timed_out: game.timed_out,
+            hints: game.hints.clone(),
+        }
+    }
+
+    ///Whether the fields of the game are the same as in the other row
+    fn same_game(&self, other: &GuessRow) -> bool {
+        self.answer.to_uppercase() == other.answer.to_uppercase() && self.difficult == other.difficult
+            && self.practice == other.practice && self.variant == other.variant && self.day == other.day
+            && self.timed_out == other.timed_out && self.hints == other.hints
+    }
+}
+
+///Feedback code of a guess
+fn feedback_code(guess: &str, answer: &str) -> String {
+    compute_state(guess, answer).iter().map(|x| ['R', 'Y', 'G'][*x as usize]).collect()
+}
+
+///One row for each guess of every game, one row for a game without guesses
+pub fn export_rows(games: &[Game], acceptable_words: &BTreeSet<String>) -> Vec<GuessRow> {
+    let mut rows = Vec::new();
+    for (index, game) in games.iter().enumerate() {
+        if game.guesses.is_empty() {
+            rows.push(GuessRow::of_game(index, game, acceptable_words.len()));
+            continue;
+        }
+        let answer = game.answer.to_lowercase();
+        let mut candidates = acceptable_words.clone();
+        for (i, guess) in game.guesses.iter().enumerate() {
+            let word = guess.to_lowercase();
+            candidates = filter_words(&candidates, &word, &compute_state(&word, &answer));
+            rows.push(GuessRow {
+                guess_number: i + 1,
+                guess: guess.clone(),
+                feedback: feedback_code(&word, &answer),
+                time_ms: game.times.get(i).copied(),
+                ..GuessRow::of_game(index, game, candidates.len())
+            });
+        }
+    }
+    rows
+}
+
+///Rows as csv with a header line
+pub fn to_csv(rows: &[GuessRow]) -> String {
+    let mut text = String::from("game,answer,guess_number,guess,feedback,candidates,difficult,time_ms,practice,variant,day,timed_out,hints\n");
+    for x in rows {
+        //提示等级以空格分隔
+        let hints: Vec<String> = x.hints.iter().map(|h| h.to_string()).collect();
+        text += &format!("{},{},{},{},{},{},{},{},{},{},{},{},{}\n", x.game, x.answer, x.guess_number, x.guess, x.feedback,
+            x.candidates, x.difficult, x.time_ms.map(|t| t.to_string()).unwrap_or_default(), x.practice, x.variant,
+            x.day.map(|d| d.to_string()).unwrap_or_default(), x.timed_out, hints.join(" "));
+    }
+    text
+}
+
+///Rows as one json object per line
+pub fn to_ndjson(rows: &[GuessRow]) -> String {
+    rows.iter().map(|x| serde_json::to_string(x).unwrap() + "\n").collect()
+}
+
+///Games of an ndjson export, the rows of each game in order
+///The feedback is checked against the guess and the answer, the candidates are only informative
+pub fn parse_ndjson(text: &str) -> Result<Vec<Game>, String> {
+    let mut games: Vec<Game> = Vec::new();
+    //上一行，同一游戏的各行须一致
+    let mut last_row: Option<GuessRow> = None;
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row: GuessRow = serde_json::from_str(line).map_err(|e| format!("line {}: {}", index + 1, e))?;
+        match &last_row {
+            Some(last) if last.game == row.game => {
+                if !row.same_game(last) {
+                    return Err(format!("line {}: the fields of game {} differ from its earlier rows", index + 1, row.game));
+                }
+                if last.guess_number == 0 {
+                    return Err(format!("line {}: game {} has no guesses", index + 1, row.game));
+                }
+            },
+            _ => games.push(Game {
+                answer: row.answer.to_uppercase(),
+                guesses: Vec::new(),
+                day: row.day,
+                difficult: row.difficult,
+                times: Vec::new(),
+                timed_out: row.timed_out,
+                hints: row.hints.clone(),
+                practice: row.practice,
+                variant: row.variant,
+            }),
+        }
+        let game = games.last_mut().unwrap();
+        if row.guess_number == 0 && game.guesses.is_empty() {
+            if !row.guess.is_empty() || !row.feedback.is_empty() || row.time_ms.is_some() {
+                return Err(format!("line {}: guess 0 of game {} should have no guess, feedback or time", index + 1, row.game));
+            }
+            last_row = Some(row);
+            continue;
+        }
+        if row.guess_number != game.guesses.len() + 1 {
+            return Err(format!("line {}: guess {} of game {} should be guess {}", index + 1, row.guess_number, row.game, game.guesses.len() + 1));
+        }
+        let expected = feedback_code(&row.guess.to_lowercase(), &row.answer.to_lowercase());
+        if row.feedback != expected {
+            return Err(format!("line {}: feedback {} of {} should be {}", index + 1, row.feedback, row.guess, expected));
+        }
+        //第一行决定该游戏是否计时
+        let timed = if game.guesses.is_empty() { row.time_ms.is_some() } else { !game.times.is_empty() };
+        if row.time_ms.is_some() != timed {
+            return Err(format!("line {}: every guess of game {} or none should have a time", index + 1, row.game));
+        }
+        game.guesses.push(row.guess.to_uppercase());
+        game.times.extend(row.time_ms);
+        last_row = Some(row);
+    }
+    Ok(games)
+}
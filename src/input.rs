@@ -0,0 +1,79 @@
+use std::io::{self, BufRead};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+///Why no line was read
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputError {
+    TimedOut,
+    Closed,
+}
+
+///Where the game reads the player's lines from
+pub trait LineInput {
+    ///Read one line, giving up at the deadline if any
+    fn read_line(&mut self, deadline: Option<Instant>) -> Result<String, InputError>;
+
+    ///First letter of the next line that is not blank
+    fn read_char(&mut self, deadline: Option<Instant>) -> Result<char, InputError> {
+        loop {
+            let line = self.read_line(deadline)?;
+            if let Some(c) = line.trim().chars().next() {
+                return Ok(c);
+            }
+        }
+    }
+}
+
+///Any buffered reader, e.g. a script of lines in a test
+///Lines are always ready, the clock is only checked by the caller
+impl<R: BufRead> LineInput for R {
+    fn read_line(&mut self, _deadline: Option<Instant>) -> Result<String, InputError> {
+        let mut line = String::new();
+        match BufRead::read_line(self, &mut line) {
+            Ok(0) | Err(_) => Err(InputError::Closed),
+            Ok(_) => Ok(line),
+        }
+    }
+}
+
+static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+
+///Lines of stdin, read on a background thread so that waiting can stop at a deadline
+///Once started, every read of stdin must go through it
+pub struct StdinLines;
+
+fn lines() -> &'static Mutex<Receiver<String>> {
+    LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || loop {
+            let mut line = String::new();
+            match io::stdin().read_line(&mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                },
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+impl LineInput for StdinLines {
+    fn read_line(&mut self, deadline: Option<Instant>) -> Result<String, InputError> {
+        let receiver = lines().lock().unwrap();
+        let deadline = match deadline {
+            Some(x) => x,
+            None => return receiver.recv().map_err(|_| InputError::Closed),
+        };
+        let left = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(left) {
+            Ok(line) => Ok(line),
+            Err(RecvTimeoutError::Timeout) => Err(InputError::TimedOut),
+            Err(RecvTimeoutError::Disconnected) => Err(InputError::Closed),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+///Alphabet, keyboard layout and word lists of one language
+#[derive(Deserialize, Debug)]
+pub struct Language {
+    pub name: String,
+    ///Every letter used by the words, in the order printed in test mode
+    pub alphabet: String,
+    ///Keyboard rows from top to bottom, leading spaces are kept as indent
+    pub keyboard: Vec<String>,
+    ///Final and acceptable word lists, relative to the pack file
+    #[serde(rename = "final")]
+    pub final_set: Option<String>,
+    #[serde(rename = "acceptable")]
+    pub acceptable_set: Option<String>,
+}
+
+impl Language {
+    ///The builtin English language
+    pub fn english() -> Self {
+        Language {
+            name: "English".to_string(),
+            alphabet: "abcdefghijklmnopqrstuvwxyz".to_string(),
+            keyboard: vec!["qwertyuiop".to_string(), " asdfghjkl".to_string(), "  zxcvbnm".to_string()],
+            final_set: None,
+            acceptable_set: None,
+        }
+    }
+
+    ///Load a language pack from its json file
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut language: Language = serde_json::from_str(&data).map_err(|e| format!("{}: {}", path, e))?;
+        language.alphabet = normalize(&language.alphabet);
+        language.keyboard = language.keyboard.iter().map(|x| x.nfc().collect::<String>().to_lowercase()).collect();
+        //词库路径相对于语言包文件
+        let dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+        let resolve = |x: &String| dir.join(x).to_string_lossy().to_string();
+        language.final_set = language.final_set.as_ref().map(resolve);
+        language.acceptable_set = language.acceptable_set.as_ref().map(resolve);
+        Ok(language)
+    }
+
+    ///Letters of the alphabet in order
+    pub fn letters(&self) -> Vec<char> {
+        self.alphabet.chars().collect()
+    }
+}
+
+///Normalize one input word: trimmed, composed (NFC) and lowercased
+pub fn normalize(word: &str) -> String {
+    word.trim().nfc().collect::<String>().to_lowercase()
+}
+
+///Uppercase one letter, keeping it if it has no single uppercase char (e.g. ß)
+pub fn upper_char(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(x), None) => x,
+        _ => c,
+    }
+}
+
+///Uppercase each letter of a word, one char for each letter
+pub fn to_upper(word: &str) -> String {
+    word.chars().map(upper_char).collect()
+}
+
+///Length of a word in user-perceived letters
+pub fn word_length(word: &str) -> usize {
+    word.graphemes(true).count()
+}
+
+///Check whether a normalized word has 5 letters, each of them a single char
+pub fn is_five_letters(word: &str) -> bool {
+    word_length(word) == 5 && word.chars().count() == 5
+}
@@ -0,0 +1,13 @@
+///Game logic shared with other programs
+pub mod feedback;
+pub mod game;
+pub mod hard_mode;
+pub mod hints;
+pub mod input;
+pub mod language;
+pub mod openers;
+pub mod query;
+pub mod solver;
+pub mod theme;
+pub mod timer;
+pub mod variant;
@@ -1,20 +1,45 @@
-use std::collections::{BTreeSet, HashMap, BinaryHeap, HashSet};
+use std::collections::{BTreeSet, HashMap, BinaryHeap};
 use std::cmp::Ordering;
-use clap::{Parser, ArgGroup};
-use rand::seq::SliceRandom;
+use clap::{Parser, Subcommand, ArgGroup, ArgMatches, CommandFactory, FromArgMatches, ValueSource};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
-use text_io::read;
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 use serde_json::to_string_pretty;
 use serde_json::{Map, Value};
 use std::fs;
-use crate::builtin_words::{FINAL, ACCEPTABLE};
+use std::num::NonZeroU64;
+use std::sync::OnceLock;
 mod builtin_words;
-use console;
-use std::io::{self, Write};
+mod validate;
+mod share;
+mod verify;
+mod replay;
+mod analytics;
+mod history;
+mod word_list;
+mod packs;
+mod difficulty;
+mod config;
+mod ui;
+use crate::language::Language;
+use crate::packs::Pack;
+use crate::difficulty::Difficulty;
+use crate::config::{Config, ConfigFile, Source};
+use crate::ui::Ui;
+use wordle::feedback::compute_state;
+use wordle::solver::{self, information_entrophy, WordEntrophy};
+use wordle::game::{self, Io, Round, Rules};
+use wordle::input::{LineInput, StdinLines};
+use wordle::theme::{self, Theme};
+use wordle::timer::SystemClock;
+use wordle::language;
+use wordle::query::{self, Query, SortBy};
+use wordle::openers::{self, RankBy};
+use wordle::variant::Variant;
+use std::io::{self, Read, Write};
 
-#[derive(Parser, Deserialize, Serialize)]
+#[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 #[clap(group(
             ArgGroup::new("mode")
@@ -23,13 +48,11 @@ use std::io::{self, Write};
 ))]
 #[clap(group(
             ArgGroup::new("rand_day")
-            .requires("random")
             .conflicts_with("word")
             .args(&["day"]),
 ))]
 #[clap(group(
             ArgGroup::new("rand_seed")
-            .requires("random")
             .conflicts_with("word")
             .args(&["seed"]),
 ))]
@@ -45,22 +68,302 @@ struct Args {
     seed: u64,
     #[clap(short = 'D', long, action)]
     difficult: bool,
+    #[clap(short = 'U', long, action)]
+    ultra: bool,
     #[clap(short = 't', long, action)]
     stats: bool,
-    #[clap(short = 'f', long = "final-set", value_parser)]
+    #[clap(short = 'f', long = "final-set", global = true, value_parser)]
     finalset: Option<String>,
-    #[clap(short = 'a', long = "acceptable-set", value_parser)]
+    #[clap(short = 'a', long = "acceptable-set", global = true, value_parser)]
     acceptableset: Option<String>,
-    #[clap(short = 'S', long, value_parser)]
+    #[clap(short = 'S', long, global = true, value_parser)]
     state: Option<String>,
+    ///Config file, json or toml, applied over the user config dir and ./wordle.toml
     #[clap(short = 'c', long, value_parser)]
     config: Option<String>,
+    #[clap(short = 'l', long, global = true, value_parser)]
+    language: Option<String>,
+    ///Builtin word pack, see `packs list`
+    #[clap(long, global = true, value_parser)]
+    pack: Option<String>,
+    ///Restrict the random answers to a difficulty band, see `ratings`
+    #[clap(long, global = true, value_enum)]
+    difficulty: Option<Difficulty>,
+    ///Seconds allowed for the whole game
+    #[clap(long, value_parser)]
+    time_limit: Option<u64>,
+    ///Seconds allowed for each guess
+    #[clap(long, value_parser)]
+    guess_time_limit: Option<u64>,
+    ///Play this many random days in a row against the clock
+    #[clap(long, conflicts_with = "word", value_parser = clap::value_parser!(u64).range(1..))]
+    speedrun: Option<u64>,
+    ///Rounds played in a row without asking to continue
+    #[clap(skip)]
+    rounds: Option<u64>,
     #[clap(short, long, value_parser)]
-    idea: Option<bool>
+    idea: Option<bool>,
+    #[clap(long, global = true, action)]
+    repair: bool,
+    ///Colors of the letters, monochrome by default if NO_COLOR is set
+    #[clap(long, global = true, value_enum)]
+    theme: Option<Theme>,
+    ///Explain the color of each letter after every guess
+    #[clap(long, action)]
+    explain: bool,
+    ///Rules changing what the feedback reveals, recorded in each saved game
+    #[clap(long, global = true, value_enum, default_value_t = Variant::Classic)]
+    variant: Variant,
+    ///Weight of the acceptable words that are not final words as answers for the solver, 0~1
+    ///They can not be the answer by default, so hints favor the final words
+    #[clap(long, global = true, value_parser)]
+    acceptable_weight: Option<f64>,
+    ///Word frequency file scaling the weights, one word and its count each line
+    #[clap(long, global = true, value_parser)]
+    frequency: Option<String>,
+    ///How the game is shown, color on a terminal and test otherwise by default
+    #[clap(long, global = true, value_enum)]
+    ui: Option<Ui>,
+    #[clap(subcommand)]
+    command: Option<Command>,
+    ///Whether the rounds are practice, set by the practice subcommand
+    #[clap(skip)]
+    practice: bool,
+}
+
+///Subcommands besides playing the game
+#[derive(Subcommand)]
+enum Command {
+    ///Check the state file against the game rules
+    Validate {
+        ///Also check the answers and guesses against the assigned word lists
+        #[clap(long, action)]
+        words: bool,
+    },
+    ///Print a stored game as a shareable grid
+    Share {
+        ///Index of the game in the state file, the last one by default
+        #[clap(value_parser)]
+        index: Option<usize>,
+        ///Use letters instead of emoji
+        #[clap(long, action)]
+        ascii: bool,
+    },
+    ///Step through a stored game guess by guess
+    Replay {
+        ///Index of the game in the state file, the last one by default
+        #[clap(value_parser)]
+        index: Option<usize>,
+    },
+    ///Letter, grey letter and candidate statistics over the games in the state file, compared with the solver
+    Analytics {
+        ///Print as csv
+        #[clap(long, action)]
+        csv: bool,
+        ///Write to a file instead of stdout
+        #[clap(short, long, value_parser)]
+        output: Option<String>,
+    },
+    ///Write every guess in the state file as one row
+    Export {
+        #[clap(long, value_enum, default_value_t = history::Format::Csv)]
+        format: history::Format,
+        ///Write to a file instead of stdout
+        #[clap(short, long, value_parser)]
+        output: Option<String>,
+    },
+    ///Append the games of an ndjson export to the state file, creating it if needed
+    Import {
+        #[clap(value_parser)]
+        path: String,
+    },
+    ///Practice without affecting the statistics, the games are tagged in the state file
+    Practice {
+        #[clap(subcommand)]
+        action: PracticeCommand,
+    },
+    ///Manage word list files
+    Lists {
+        #[clap(subcommand)]
+        action: ListsCommand,
+    },
+    ///Check a grid pasted from stdin against the answer of a day
+    Verify {
+        ///Day of the answer, taken from the grid header if omitted
+        #[clap(value_parser)]
+        day: Option<usize>,
+        ///Seed used to arrange the answers of each day
+        #[clap(long, default_value_t = 114514, value_parser)]
+        seed: u64,
+        ///Most possible guesses listed for each row
+        #[clap(long, default_value_t = 10, value_parser)]
+        limit: usize,
+    },
+    ///Show the difficulty rating of every answer
+    Ratings {
+        ///Print as csv
+        #[clap(long, action)]
+        csv: bool,
+        ///Write to a file instead of stdout
+        #[clap(short, long, value_parser)]
+        output: Option<String>,
+    },
+    ///Play the same seeded answers as everyone else, or score the results
+    Tournament {
+        #[clap(long, default_value_t = 114514, value_parser)]
+        seed: u64,
+        #[clap(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        rounds: u64,
+        ///Name of the player, results go to <PLAYER>.json unless -S is used
+        #[clap(long, value_parser)]
+        player: Option<String>,
+        #[clap(subcommand)]
+        action: Option<TournamentCommand>,
+    },
+    ///Show the builtin word packs
+    Packs {
+        #[clap(subcommand)]
+        action: PacksCommand,
+    },
+    ///List the words fitting the known letters, e.g. `query --pattern _R_A_ --include E --exclude S`
+    Query {
+        ///Correct letters, `_` for unknown positions
+        #[clap(short, long, value_parser)]
+        pattern: Option<String>,
+        ///Letters somewhere in the word, repeated for more copies
+        #[clap(short, long, default_value = "", value_parser)]
+        include: String,
+        ///Letters not in the word
+        #[clap(short, long, default_value = "", value_parser)]
+        exclude: String,
+        ///Misplaced letters as a pattern, e.g. `__E__`, can be used more than once
+        #[clap(short, long, value_parser)]
+        misplaced: Vec<String>,
+        #[clap(long, value_enum, default_value_t = SortBy::Alpha)]
+        sort: SortBy,
+        ///Most words listed
+        #[clap(long, value_parser)]
+        limit: Option<usize>,
+        ///Print as json
+        #[clap(long, action)]
+        json: bool,
+        ///Word list to search, the acceptable set by default
+        #[clap(value_parser)]
+        path: Option<String>,
+    },
+    ///Rank the first guesses against the final words, or break down the patterns of one
+    Openers {
+        ///Show the 243 pattern buckets of this opener instead
+        #[clap(value_parser)]
+        opener: Option<String>,
+        #[clap(long, value_enum, default_value_t = RankBy::Entropy)]
+        sort: RankBy,
+        ///Openers listed
+        #[clap(long, default_value_t = 10, value_parser)]
+        top: usize,
+        ///Also play every final word with the solver after each listed opener
+        #[clap(long, action)]
+        benchmark: bool,
+    },
+    ///Play every final word with the solver and show the average guess attempts
+    Benchmark,
+    ///Inspect the layered config
+    Config {
+        #[clap(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+///What to practice
+#[derive(Subcommand)]
+enum PracticeCommand {
+    ///Replay the answer of a past day of the random mode
+    Day {
+        #[clap(value_parser)]
+        day: usize,
+    },
+    ///Drill the answers failed in the state file and not solved since
+    Failed,
+    ///Play the final words fitting the known letters in a random order, see `query`
+    Pattern {
+        ///Correct letters, `_` for unknown positions
+        #[clap(value_parser)]
+        pattern: String,
+        ///Letters somewhere in the word, repeated for more copies
+        #[clap(short, long, default_value = "", value_parser)]
+        include: String,
+        ///Letters not in the word
+        #[clap(short, long, default_value = "", value_parser)]
+        exclude: String,
+        ///Misplaced letters as a pattern, e.g. `__E__`, can be used more than once
+        #[clap(short, long, value_parser)]
+        misplaced: Vec<String>,
+    },
+}
+
+///Subcommands for the config
+#[derive(Subcommand)]
+enum ConfigCommand {
+    ///Print every effective value and where it came from
+    Show,
+}
+
+///Subcommands for tournaments
+#[derive(Subcommand)]
+enum TournamentCommand {
+    ///Merge the player files and rank the players
+    Score {
+        #[clap(value_parser, required = true)]
+        paths: Vec<String>,
+    },
+}
+
+///Subcommands for builtin word packs
+#[derive(Subcommand)]
+enum PacksCommand {
+    ///List the packs with their sizes
+    List,
+    ///Compute the entrophy data of a pack as json
+    Entrophy {
+        #[clap(value_parser)]
+        name: String,
+    },
+    ///Compute the best first guesses of a pack for the hints as json
+    Openings {
+        #[clap(value_parser)]
+        name: String,
+    },
+}
+
+///Subcommands for word list files
+#[derive(Subcommand)]
+enum ListsCommand {
+    ///Check the assigned final and acceptable sets, line by line
+    Validate,
+    ///Show the words only in one of two lists
+    Diff {
+        #[clap(value_parser)]
+        first: String,
+        #[clap(value_parser)]
+        second: String,
+    },
+    ///Merge lists into one sorted list without duplicates
+    Merge {
+        #[clap(value_parser, required = true)]
+        paths: Vec<String>,
+        ///Write to a file instead of stdout
+        #[clap(short, long, value_parser)]
+        output: Option<String>,
+    },
+    ///Show the letter frequency of a list, the final set by default
+    Stats {
+        #[clap(value_parser)]
+        path: Option<String>,
+    },
 }
 
 ///Tuple for one word(string) and its appearence(i32)
-#[derive(Debug, Eq, Ord)]
+#[derive(Debug, Eq)]
 struct WordDict (String, i32);
 
 impl PartialEq for WordDict {
@@ -71,30 +374,16 @@ impl PartialEq for WordDict {
 
 impl PartialOrd for WordDict {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        match self.1.cmp(&other.1) {
-            Ordering::Greater => Some(Ordering::Greater),
-            Ordering::Less => Some(Ordering::Less),
-            Ordering::Equal => Some(self.0.cmp(&other.0).reverse())
-        }
-    }
-}
-
-///Tuple for one word(string) and its information entrophy
-#[derive(Debug, Eq, Ord)]
-struct WordEntrophy (String, i64);
-
-impl PartialEq for WordEntrophy {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
+        Some(self.cmp(other))
     }
 }
 
-impl PartialOrd for WordEntrophy {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+impl Ord for WordDict {
+    fn cmp(&self, other: &Self) -> Ordering {
         match self.1.cmp(&other.1) {
-            Ordering::Greater => Some(Ordering::Greater),
-            Ordering::Less => Some(Ordering::Less),
-            Ordering::Equal => Some(self.0.cmp(&other.0).reverse())
+            Ordering::Greater => Ordering::Greater,
+            Ordering::Less => Ordering::Less,
+            Ordering::Equal => self.0.cmp(&other.0).reverse()
         }
     }
 }
@@ -103,1213 +392,1296 @@ impl PartialOrd for WordEntrophy {
 struct GameResult {
     win: bool,
     attempt: i32,
-    word_list: HashMap<String, i32>
+    word_list: HashMap<String, i32>,
+    ///Milliseconds taken for each guess in timed games
+    times: Vec<u64>,
+    ///Level of each hint used
+    hints: Vec<u8>
 }
 
 ///Answer and guesses for one game round
 #[derive(Deserialize, Serialize, Debug)]
 struct Game {
     answer: String,
-    guesses: Vec<String>
+    guesses: Vec<String>,
+    ///Day of the answer in random mode, the number of the shared grid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    day: Option<usize>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    difficult: bool,
+    ///Milliseconds taken for each guess, only in timed games
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    times: Vec<u64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    timed_out: bool,
+    ///Level of each hint used, the penalty is the sum of the levels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hints: Vec<u8>,
+    ///Played in the practice mode, left out of the statistics and streaks
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    practice: bool,
+    ///Feedback rules of the game
+    #[serde(default, skip_serializing_if = "Variant::is_classic")]
+    variant: Variant,
 }
 
 ///All games used to load into json
 #[derive(Deserialize, Serialize, Debug)]
 struct User {
+    ///Name of the player in a tournament
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    player: Option<String>,
     total_rounds: Option<i32>,
     games: Option<Vec<Game>>
 }
 
-///Compute each words' information entrophy in the library
-fn information_entrophy(library: &BTreeSet<String>) -> BinaryHeap<WordEntrophy> {
-    let mut result: BinaryHeap<WordEntrophy> = BinaryHeap::new();
-    let mut state = [[0; 5]; 243];
-    for i in 1..243 {
-        let mut num = i;
-        state[i][0] = num / 81;
-        num %= 81;
-        state[i][1] = num / 27;
-        num %= 27;
-        state[i][2] = num / 9;
-        num %= 9;
-        state[i][3] = num / 3;
-        num %= 3;
-        state[i][4] = num;
-    }//生成状态数组共3^5种
-    //2->Green 1->Yellow 0->Red
-    
-    for word_target in library {
-        let mut index = 0;
-        let mut flag_list: HashSet<String> = HashSet::new();
-        let mut condition = [0; 243];
-        for state in &state {
-            for word in library {
-                if flag_list.contains(word) { continue; }
-                //新建target字符集映射，反映各字母出现次数
-                let mut target_map: HashMap<char, i32> = HashMap::new();
-                for c in word_target.chars() {
-                    if let Some(x) = target_map.get_mut(&c) {
-                    *x += 1;
-                    }
-                    target_map.entry(c).or_insert(1);
-                }
-                let mut map: HashMap<char, i32> = HashMap::new();
-                for c in word.chars() {
-                    if let Some(x) = map.get_mut(&c) {
-                    *x += 1;
-                    }
-                    map.entry(c).or_insert(1);
-                }
-                let mut flag = true;
-                //Green
-                for i in 0..5 {
-                    if state[i] == 2 && word.chars().nth(i) != word_target.chars().nth(i) {
-                        flag = false;
-                        break;
-                    }
-                    else if state[i] == 2 && word.chars().nth(i) == word_target.chars().nth(i) {
-                        if let Some(x) = word.chars().nth(i) {
-                            if let Some(z) = target_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                        }
-                    }
-                }
-                if !flag { continue; }
-                //Yellow
-                for i in 0..5 {
-                    if state[i] == 1 {
-                        if word.chars().nth(i) == word_target.chars().nth(i) { flag = false; break; }
-                        else if let Some(x) = word_target.chars().nth(i) {
-                            if let Some(z) = map.get_mut(&x) {
-                                if *z == 0 { flag = false; break; }
-                                else { *z -= 1; }
-                            }
-                            else { flag = false; break; }
-                        }
-                    }
-                }
-                if !flag { continue; }
-                //Red
-                for i in 0..5 {
-                    if state[i] == 0 {
-                        if let Some(x) = word_target.chars().nth(i) {
-                            if let Some(z) = map.get_mut(&x) {
-                                if *z != 0 { flag = false; break; }
-                            }
-                        }
-                    }
-                }
-                if !flag { continue; }
-                if flag {
-                    condition[index] += 1;
-                    flag_list.insert(word.clone());
-                }
-            }
-            index += 1;
-        }       
-        let mut sum: f64 = 0.0;
-        for i in condition {
-            let p = i as f64 / library.len() as f64;
-            if i != 0 && i != 1 {
-                sum += p * (1.0 / p).log2();
-            }
+///The builtin pack selected in args
+fn selected_pack(args: &Args) -> &'static Pack {
+    let name = args.pack.as_deref().unwrap_or(packs::DEFAULT_PACK);
+    match packs::find(name) {
+        Some(x) => x,
+        None => {
+            let names: Vec<&str> = packs::PACKS.iter().map(|x| x.name).collect();
+            eprintln!("Unknown pack {}, choose from: {}", name, names.join(" "));
+            std::process::exit(1);
         }
-        //println!("{} {}",word_target, sum);
-        result.push(WordEntrophy(word_target.clone(), (sum * 1000000000.0) as i64));
     }
-    result
 }
 
-///Spawn required index in FINAL list
-fn random_spawn(day: usize, seed: u64, size: usize) -> usize{
-    let len = size;
-    let mut list: Vec<usize> = Vec::new();
-    let mut i = 0;
-    while i < len {
-        list.push(i);
-        i += 1;
-    }
-    let mut r = StdRng::seed_from_u64(seed);
-    list.shuffle(&mut r);
-    list[day - 1]
+///Information entrophy of the acceptable words, precomputed unless a file is assigned
+fn initial_entrophy(args: &Args, acceptable_words: &BTreeSet<String>) -> BinaryHeap<WordEntrophy> {
+    match &args.acceptableset {
+        Some(_) => information_entrophy(acceptable_words),
+        None => selected_pack(args).entrophy(),
+    }
 }
 
-///Read txt files and load into vector
-fn read_to_list(path: String) -> BTreeSet<String> {
-    let model_filename: String = std::fs::read_to_string(path).unwrap();
-
-    let result: BTreeSet<String> = model_filename
-        .lines()
-        .into_iter()
-        .map(move |ch| ch.to_string().to_lowercase())
-        .collect();
-    let mut returnvalue: BTreeSet<String> = BTreeSet::new();
-    for x in &result {
-        let mut flag = true;
-        for c in x.chars() {
-            if !c.is_alphabetic() || x.len() != 5 {
-                flag = false;
-                break;
-            }
-        }
-        if flag {
-            returnvalue.insert(x.clone());
-        }
-        else {
-            panic!("Invalid file assigned");
-        }
+///Likelihood of the answers for the solver, the acceptable-only words can not be the answer by default
+fn answer_weights(args: &Args, final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>) -> solver::Weights {
+    let other_weight = args.acceptable_weight.unwrap_or(0.0);
+    if !(0.0..=1.0).contains(&other_weight) {
+        eprintln!("Invalid acceptable weight {}, it should be 0~1", other_weight);
+        std::process::exit(1);
     }
-    returnvalue
+    let frequency = load_frequency(args);
+    solver::answer_weights(final_words, acceptable_words, other_weight, frequency.as_ref())
 }
 
-///Read json files and load into User
-fn read_from_file_user(path: &String) -> User{
-    let data = fs::read_to_string(path).unwrap();
+///Best first guesses for the hints, precomputed for the packs with the default weights
+fn opening_ranking(args: &Args, acceptable_words: &BTreeSet<String>, weights: &solver::Weights) -> BinaryHeap<WordEntrophy> {
+    let default_weights = args.acceptable_weight.is_none() && args.frequency.is_none();
+    if default_weights && args.finalset.is_none() && args.acceptableset.is_none() {
+        selected_pack(args).openings()
+    }
+    else {
+        solver::weighted_guess_scores(acceptable_words, weights)
+    }
+}
 
-    let u: User = serde_json::from_str(&data).unwrap();
-    u
+///Word frequency table of --frequency, exit if the file is invalid
+fn load_frequency(args: &Args) -> Option<HashMap<String, f64>> {
+    args.frequency.as_ref().map(|path| match word_list::read_frequency(path) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Invalid frequency file, {}", e);
+            std::process::exit(1);
+        }
+    })
 }
 
-///Read json files and load into Args
-fn read_from_file_config(path: &String) -> Args{
-    let data = fs::read_to_string(path).unwrap();
+static RATINGS: OnceLock<Vec<difficulty::Rating>> = OnceLock::new();
 
-    let u: Args = serde_json::from_str(&data).unwrap();
-    u
+///Rate the final words by the solver's games from its opening guess and the --frequency table
+///The word lists do not change between rounds, so the ratings are computed once
+fn rate_answers(args: &Args, final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>) -> &'static [difficulty::Rating] {
+    RATINGS.get_or_init(|| {
+        let opener = match initial_entrophy(args, acceptable_words).peek() {
+            Some(x) => x.0.clone(),
+            None => String::new(),
+        };
+        difficulty::rate_words(final_words, acceptable_words, &opener, load_frequency(args).as_ref())
+    })
 }
 
-///Normal state for one wordle round
-fn game_round_normal(args: &Args) -> Option<GameResult> {
-    let mut final_words: BTreeSet<String> = BTreeSet::new();
-    let mut acceptable_words: BTreeSet<String> = BTreeSet::new();
-    let mut final_flag = false;
-    let mut acceptable_flag = false;
-    let mut entrophy: BinaryHeap<WordEntrophy> = BinaryHeap::new();
-    //导入文件词库
-    if let Some(path) = &args.finalset {
-        final_words = read_to_list(path.clone());
-        final_flag = true;
+///Answers the random mode picks from, limited to the difficulty band if assigned
+fn answer_pool(args: &Args, final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>) -> BTreeSet<String> {
+    let pool = match args.difficulty {
+        Some(x) => difficulty::words_in_band(rate_answers(args, final_words, acceptable_words), x),
+        None => final_words.clone(),
+    };
+    if args.random && (args.day == 0 || args.day > pool.len()) {
+        eprintln!("Day {} out of range, {} answers in total", args.day, pool.len());
+        std::process::exit(1);
     }
-    else {
-        for word in FINAL {
-            final_words.insert(word.to_string());
+    pool
+}
+
+///Read txt files and load into vector
+fn read_to_list(path: String) -> BTreeSet<String> {
+    let parsed = match word_list::read_list(&path) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Invalid file assigned, {}", e);
+            std::process::exit(1);
         }
+    };
+    if !parsed.invalid.is_empty() {
+        for (line, content) in &parsed.invalid {
+            eprintln!("{}:{}: invalid word \"{}\"", path, line, content);
+        }
+        eprintln!("Invalid file assigned");
+        std::process::exit(1);
+    }
+    parsed.words
+}
+
+///Exit if some final words are not acceptable
+fn check_word_sets(final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>) {
+    let missing = word_list::missing_words(final_words, acceptable_words);
+    if !missing.is_empty() {
+        eprintln!("Invalid word set--not included: {}", missing.join(" "));
+        std::process::exit(1);
     }
-    if let Some(path) = &args.acceptableset {
-        acceptable_words = read_to_list(path.clone());
-        acceptable_flag = true;
-        entrophy = information_entrophy(&acceptable_words);
+}
+
+///Exit if some words use letters outside the alphabet of the language
+fn check_alphabet(words: &BTreeSet<String>, language: &Language) {
+    let letters = language.letters();
+    let outside: Vec<String> = words
+        .iter()
+        .filter(|x| x.chars().any(|c| !letters.contains(&c)))
+        .map(|x| language::to_upper(x))
+        .collect();
+    if !outside.is_empty() {
+        eprintln!("Invalid word set--letters not in the {} alphabet: {}", language.name, outside.join(" "));
+        std::process::exit(1);
     }
-    else {
-        for word in ACCEPTABLE {
-            acceptable_words.insert(word.to_string());
+}
+
+///Load the final and acceptable word sets assigned in args
+fn load_word_sets(args: &Args) -> (BTreeSet<String>, BTreeSet<String>) {
+    let final_words: BTreeSet<String> = match &args.finalset {
+        Some(path) => read_to_list(path.clone()),
+        None => selected_pack(args).final_words(),
+    };
+    let acceptable_words: BTreeSet<String> = match &args.acceptableset {
+        Some(path) => read_to_list(path.clone()),
+        None => selected_pack(args).acceptable_words(),
+    };
+    (final_words, acceptable_words)
+}
+
+///Validate the state file, dropping bad games if --repair is used
+///The words are only checked against the current lists if asked, games may come from other lists
+///Return the number of bad games left in the file
+fn check_state_file(args: &Args, path: &String, check_words: bool) -> usize {
+    let (final_words, acceptable_words) = load_word_sets(args);
+    let lists = if check_words { Some((&final_words, &acceptable_words)) } else { None };
+    let mut u = read_from_file_user(path);
+    if args.repair {
+        let dropped = validate::repair_user(&mut u, lists);
+        for (index, errors) in &dropped {
+            for error in errors {
+                eprintln!("Dropped game {}: {}", index + 1, error);
+            }
         }
-        let config = fs::read_to_string("src/acceptable.json").unwrap();
-        let parsed: Value = serde_json::from_str(&config).unwrap();
-        let obj: Map<String, Value> = parsed.as_object().unwrap().clone();
-        for i in obj {
-            let x: i64 = serde_json::from_value(i.1).unwrap();
-            entrophy.push(WordEntrophy(i.0.clone(), x));
+        if !dropped.is_empty() {
+            if let Err(e) = fs::write(path, to_string_pretty(&u).unwrap()) {
+                eprintln!("Cannot write state file {}: {}", path, e);
+                std::process::exit(1);
+            }
         }
+        0
     }
-    let mut reasonable_words = acceptable_words.clone();//指示针对猜测是否是合法单词集
-    //检查是否是子集
-    if final_flag || acceptable_flag {
-        let mut flag = true;
-        for word in &final_words {
-            if !acceptable_words.contains(word) {
-                flag = false;
-                break;
+    else {
+        let invalid = validate::validate_user(&u, lists);
+        for (index, errors) in &invalid {
+            for error in errors {
+                eprintln!("Invalid game {}: {}", index + 1, error);
             }
         }
-        if !flag { panic!("Invalid word set--not included."); }
+        invalid.len()
     }
+}
 
-    let mut return_value: Option<GameResult>= None;
-    if args.stats {
-        return_value = Some(GameResult{ win:false, attempt:0, word_list: HashMap::new()});
+///Get the assigned state file, exit if there is none
+fn state_path(args: &Args) -> &String {
+    match &args.state {
+        Some(path) => path,
+        None => {
+            eprintln!("No state file assigned, use -S/--state to assign one");
+            std::process::exit(1);
+        }
     }
-    if args.difficult {
-        println!("{}! You choosed {} mode!",console::style("Warning").bold().red(),console::style("DIFFICULT").bold().red())
+}
+
+///Get one game from the state file by its index starting from 1, the last one by default
+fn stored_game(args: &Args, index: Option<usize>) -> (usize, Game) {
+    let mut games = read_from_file_user(state_path(args)).games.unwrap_or_default();
+    let index = index.unwrap_or(games.len());
+    if index == 0 || index > games.len() {
+        eprintln!("No game {} in the state file, {} game(s) stored", index, games.len());
+        std::process::exit(1);
     }
-    let mut answer;
-    if args.random {//随机模式启动
-        let index = random_spawn(args.day, args.seed, final_words.len());
-        let mut final_words_vec: Vec<&String> = Vec::new();
-        for word in &final_words {
-            final_words_vec.push(word);
-        }
-        answer = final_words_vec[index].to_string();
+    (index, games.swap_remove(index - 1))
+}
+
+///Print the report of one list file, return whether it is valid
+fn report_list(name: &str, parsed: &word_list::ParsedList) -> bool {
+    println!("{}: {} word(s), {} invalid line(s), {} duplicate(s), {} blank line(s)",
+        name, parsed.words.len(), parsed.invalid.len(), parsed.duplicates.len(), parsed.blank.len());
+    for (line, content) in &parsed.invalid {
+        println!("  line {}: invalid word \"{}\"", line, content);
     }
-    else if let Some(ans) = &args.word {//答案已指定
-        answer = ans.to_lowercase().trim().to_string();
+    for (line, word, first) in &parsed.duplicates {
+        println!("  line {}: duplicate word \"{}\", first on line {}", line, word, first);
     }
-    else {
-        //输入answer
-        loop {
-            print!("Please input the answer here: ");
-            answer = String::new();
-            io::stdout().flush().unwrap();
-            io::stdin().read_line(&mut answer).unwrap();
-            answer = answer.to_lowercase().trim().to_string();
-            if final_words.contains(&answer) { break; }
-            else { println!("Invalid answer! Please try again!"); }
-        }
+    for line in &parsed.blank {
+        println!("  line {}: blank line", line);
     }
-    println!("Answer assigned: {} :)", answer.to_uppercase());
+    parsed.invalid.is_empty()
+}
 
-    let mut u: User = User { total_rounds: None, games: None };//Json文件的结构体格式
-    let mut game = Game{answer: answer.to_uppercase().clone(), guesses: Vec::new()};
-    if let Some(path) = &args.state {//需要加载状态Json文件
-        u = read_from_file_user(path);
-        if let Some(ref mut x ) = u.total_rounds {
-            *x += 1;
-        }
-        else {
-            u.total_rounds = Some(1);
-        }
-        if let None = u.games {
-            u.games = Some(Vec::new());
+///Read a list file for the lists subcommands, exit if it cannot be read
+fn read_list_or_exit(path: &str) -> word_list::ParsedList {
+    match word_list::read_list(path) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
     }
-    //字母表映射各字符状态
-    let mut alphabet: HashMap<char, char> = HashMap::new();
-    for c in 'a'..'{' {
-        alphabet.insert(c, 'X');
-    }
-    //困难模式使用的判断
-    let mut yellow_letters: Vec<char> = Vec::new();
-    let mut green_letters= ['0';5];
+}
 
-    let mut chances = 1;
-    while chances <=6 {
-        //新建answer字符集映射
-        let mut answer_map: HashMap<char, i32> = HashMap::new();
-        for c in answer.chars() {
-            if let Some(x) = answer_map.get_mut(&c) {
-                *x += 1;
-            }
-            answer_map.entry(c).or_insert(1);
-        }
-        //输入guess
-        //信息熵提示
-        let mut i = 0;
-        print!("Do you want any suggestions? {}/{} ",
-                        console::style("[Y]").bold().yellow(),
-                        console::style("[N]").bold().red());
-        let command: char = read!();
-        if command == 'Y' {
-            println!("Possible guesses: {} in total", console::style(reasonable_words.len()).bold().yellow());
-            println!("{} and their {}:",
-                            console::style("Most possible words").bold().green(),
-                            console::style("Information Entrophy").bold().red());
-            while !entrophy.is_empty() && i < 5 {
-                if let Some(word) = entrophy.pop(){
-                    println!("{} {:.4}",
-                        console::style(&word.0.to_uppercase()).bold().green(),
-                        console::style(word.1 as f64 / 1000000000.0).bold().red());
-                }
-                i += 1;
-            }
-        }
-        print!("Please input your guess here: ");
-        io::stdout().flush().unwrap();
-        let mut guess = String::new();
-        io::stdin().read_line(&mut guess).unwrap();
-        guess = guess.to_lowercase().trim().to_string();
-        let guess_str = &guess[..];
-        //不合法判断处理
-        if guess.len() != 5 || !ACCEPTABLE.contains(&guess_str) {
-            println!("Invalid input! :( Please guess again!");
-            continue;
-        }
-        else {
-            let mut flag = true;
-            for c in guess.chars() {
-                if !c.is_alphabetic() {
-                    println!("Invalid input! :( Please guess again!");
-                    flag = false;
-                    break;
-                }
-            }
-            if !flag {
-                continue;
-            }
-        }
-        //合法情况
-        
-        if guess == answer {//直接猜出答案
-            println!("{}",console::style(answer.to_uppercase()).green());
-            println!("Correct! :D You tried {} times.", chances);
-            if args.stats {
-                if let Some(ref mut x) = return_value {
-                    x.win = true;
-                    x.attempt = chances;
-                    let count = x.word_list.entry(guess.clone()).or_insert(0);
-                    *count+=1;
-                }
-            }
-            game.guesses.push(guess.to_uppercase().clone());
-            if let Some(ref mut x) = u.games {
-                x.push(game);
-            }
-            let result = to_string_pretty(&u).unwrap();
-            if let Some(path) = &args.state{
-                fs::write(path, result).unwrap();
-            }
-            return return_value;
-        }
-        let mut show = ['X'; 5];
-        if !args.difficult {//非困难模式
-            if args.stats {
-                if let Some(ref mut x) = return_value {
-                    let count = x.word_list.entry(guess.clone()).or_insert(0);
-                    *count+=1;
-                }
-            }
-            game.guesses.push(guess.to_uppercase().clone());
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                        if let Some(y) = answer.chars().nth(i) {
-                            if x == y {
-                                show[i] = 'G';
-                                if let Some(z) = answer_map.get_mut(&x) {
-                                    *z -= 1;
-                                }
-                                if let Some(z) = alphabet.get_mut(&x) {
-                                    *z = 'G';
-                                }
-                            }
-                        }
-                    }
-                }
-            for i in 0..5 {
-            if let Some(x) = guess.chars().nth(i) {
-                    if show[i] != 'G' {
-                        if answer_map.get(&x) == Some(&0) || answer_map.get(&x) == None {
-                            show[i] = 'R';
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z == 'X' {
-                                    *z = 'R'; 
-                                }
-                            }
-                        }
-                        else {
-                            show[i] = 'Y';
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z != 'G' {
-                                    *z = 'Y'; 
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        //困难模式
-        else {
-            let mut flag =true;
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if green_letters[i] != '0' && green_letters[i] != x {
-                        println!("Invalid input for {} letters not int place! :( Please guess again!", console::style("GREEN").green());
-                        flag = false;
-                        break;
-                    }
-                }
-            }
-            for i in yellow_letters.clone() {
-                if !guess.contains(i) {
-                        println!("Invalid input for {} letters not used! :( Please guess again!", console::style("YELLOW").yellow());
-                        flag = false;
-                        break;
-                }
+///Run one of the lists subcommands
+fn run_lists_command(args: &Args, action: &ListsCommand) {
+    match action {
+        ListsCommand::Validate => {
+            let mut valid = true;
+            let final_words = match &args.finalset {
+                Some(path) => {
+                    let parsed = read_list_or_exit(path);
+                    valid &= report_list(path, &parsed);
+                    parsed.words
+                },
+                None => selected_pack(args).final_words(),
+            };
+            let acceptable_words = match &args.acceptableset {
+                Some(path) => {
+                    let parsed = read_list_or_exit(path);
+                    valid &= report_list(path, &parsed);
+                    parsed.words
+                },
+                None => selected_pack(args).acceptable_words(),
+            };
+            let missing = word_list::missing_words(&final_words, &acceptable_words);
+            if !missing.is_empty() {
+                println!("{} final word(s) not in the acceptable set: {}", missing.len(), missing.join(" "));
+                valid = false;
             }
-            if !flag {
-                continue;
-            }
-            if args.stats {
-                if let Some(ref mut x) = return_value {
-                    let count = x.word_list.entry(guess.clone()).or_insert(0);
-                    *count+=1;
-                }
+            if !valid {
+                std::process::exit(1);
             }
-            game.guesses.push(guess.to_uppercase().clone());
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if let Some(y) = answer.chars().nth(i) {
-                        if x == y {//绿色
-                            show[i] = 'G';
-                            green_letters[i] = x;
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                *z = 'G';
-                            }
-                        }
-                    }
-                }
+            println!("Word lists are valid");
+        },
+        ListsCommand::Diff { first, second } => {
+            let first_words = read_list_or_exit(first).words;
+            let second_words = read_list_or_exit(second).words;
+            for word in first_words.difference(&second_words) {
+                println!("- {}", word);
             }
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if show[i] != 'G' {
-                        if answer_map.get(&x) == Some(&0) || answer_map.get(&x) == None {//红色
-                            show[i] = 'R'; 
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z == 'X' {
-                                    *z = 'R'; 
-                                }
-                            }
-                        }
-                        else {//黄色
-                            show[i] = 'Y';
-                            yellow_letters.push(x);
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z != 'G' {
-                                    *z = 'Y'; 
-                                }
-                            }
-                        }
-                    }
-                }
+            for word in second_words.difference(&first_words) {
+                println!("+ {}", word);
             }
-        }
-        //颜色输出结果
-        let mut state = [0; 5];
-        for i in 0..5 {
-            let color = show[i];
-            if let Some(letter) = guess.chars().nth(i) {
-                match color {
-                    'G' => { print!("{}", console::style(letter.to_uppercase()).green()); state[i] = 2; },
-                    'Y' => { print!("{}", console::style(letter.to_uppercase()).yellow()); state[i] = 1; },
-                    'R' => { print!("{}", console::style(letter.to_uppercase()).red()); state[i] = 0; },
-                     _ => unimplemented!()
-                }
-            }  
-        }
-        println!("");
-        for letter in ['q','w','e','r','t','y','u','i','o','p'] {
-            if let Some(color) = alphabet.get_mut(&letter) {
-                match color {
-                    'G' => { print!("{}", console::style(letter.to_uppercase()).green()); },
-                    'Y' => { print!("{}", console::style(letter.to_uppercase()).yellow()); },
-                    'R' => { print!("{}", console::style(letter.to_uppercase()).red()); },
-                    'X' => { print!("{}", console::style(letter.to_uppercase()).dim()); },
-                     _ => unimplemented!()
+            println!("{} word(s) only in {}, {} word(s) only in {}",
+                first_words.difference(&second_words).count(), first,
+                second_words.difference(&first_words).count(), second);
+        },
+        ListsCommand::Merge { paths, output } => {
+            let mut words: BTreeSet<String> = BTreeSet::new();
+            for path in paths {
+                let parsed = read_list_or_exit(path);
+                for (line, content) in &parsed.invalid {
+                    eprintln!("{}:{}: invalid word \"{}\" skipped", path, line, content);
                 }
+                words.extend(parsed.words);
             }
-        }
-        println!("");
-        print!(" ");
-        for letter in ['a','s','d','f','g','h','j','k','l'] {
-            if let Some(color) = alphabet.get_mut(&letter) {
-                match color {
-                    'G' => { print!("{}", console::style(letter.to_uppercase()).green()); },
-                    'Y' => { print!("{}", console::style(letter.to_uppercase()).yellow()); },
-                    'R' => { print!("{}", console::style(letter.to_uppercase()).red()); },
-                    'X' => { print!("{}", console::style(letter.to_uppercase()).dim()); },
-                     _ => unimplemented!()
-                }
+            let mut text = String::new();
+            for word in &words {
+                text += word;
+                text.push('\n');
             }
-        }
-        println!("");
-        print!("  ");
-        for letter in ['z','x','c','v','b','n','m'] {
-            if let Some(color) = alphabet.get_mut(&letter) {
-                match color {
-                    'G' => { print!("{}", console::style(letter.to_uppercase()).green()); },
-                    'Y' => { print!("{}", console::style(letter.to_uppercase()).yellow()); },
-                    'R' => { print!("{}", console::style(letter.to_uppercase()).red()); },
-                    'X' => { print!("{}", console::style(letter.to_uppercase()).dim()); },
-                     _ => unimplemented!()
-                }
+            match output {
+                Some(path) => {
+                    write_output(path, &text);
+                    println!("{} word(s) written to {}", words.len(), path);
+                },
+                None => print!("{}", text),
             }
-        }
-        //更新信息熵集
-        entrophy.clear();
-        let reasonable_words_copy = reasonable_words.clone();
-        reasonable_words.clear();
-        for word in &reasonable_words_copy{
-            let mut flag = true;
-            let mut target_map: HashMap<char, i32> = HashMap::new();
-            for c in guess.chars() {
-                if let Some(x) = target_map.get_mut(&c) {
-                    *x += 1;
+        },
+        ListsCommand::Stats { path } => {
+            let words: BTreeSet<String> = match path {
+                Some(path) => read_list_or_exit(path).words,
+                None => load_word_sets(args).0,
+            };
+            println!("Words: {}", words.len());
+            println!("Letter Total  Words  1st  2nd  3rd  4th  5th");
+            for (letter, total, positions) in word_list::letter_frequency(&words) {
+                let in_words = words.iter().filter(|x| x.contains(letter)).count();
+                print!("{}      {:>5} {:>5.1}%", language::upper_char(letter), total,
+                    in_words as f64 / words.len() as f64 * 100.0);
+                for x in positions {
+                    print!(" {:>4}", x);
                 }
-                target_map.entry(c).or_insert(1);
+                println!();
             }
-            let mut map: HashMap<char, i32> = HashMap::new();
-            for c in word.chars() {
-                if let Some(x) = map.get_mut(&c) {
-                    *x += 1;
-                }
-                map.entry(c).or_insert(1);
-            }
-            //Green
-            for i in 0..5 {
-                if state[i] == 2 && word.chars().nth(i) != guess.chars().nth(i) {
-                    flag = false;
-                    break;
-                }
-                else if state[i] == 2 && word.chars().nth(i) == guess.chars().nth(i) {
-                    if let Some(x) = word.chars().nth(i) {
-                        if let Some(z) = target_map.get_mut(&x) {
-                            *z -= 1;
-                        }
-                        if let Some(z) = map.get_mut(&x) {
-                            *z -= 1;
-                        }
-                    }
-                }
+        },
+    }
+}
+
+///Number of best first guesses saved by `packs openings`, more than the suggestions show
+const OPENINGS_SAVED: usize = 100;
+
+///Run one of the packs subcommands
+fn run_packs_command(action: &PacksCommand) {
+    match action {
+        PacksCommand::List => {
+            println!("Name      Final Acceptable Description");
+            for pack in packs::PACKS {
+                println!("{:<8} {:>6} {:>10} {}", pack.name, pack.final_words().len(),
+                    pack.acceptable_words().len(), pack.description);
             }
-            if !flag { continue; }
-            //Yellow
-            for i in 0..5 {
-                if state[i] == 1 {
-                    if word.chars().nth(i) == guess.chars().nth(i) { flag = false; break; }
-                    else if let Some(x) = guess.chars().nth(i) {
-                        if let Some(z) = map.get_mut(&x) {
-                            if *z == 0 { flag = false; break; }
-                            else { *z -= 1; }
-                        }
-                        else { flag = false; break; }
-                    }
+        },
+        PacksCommand::Entrophy { name } => {
+            let pack = match packs::find(name) {
+                Some(x) => x,
+                None => {
+                    eprintln!("Unknown pack {}", name);
+                    std::process::exit(1);
                 }
+            };
+            let mut result: Map<String, Value> = Map::new();
+            for x in information_entrophy(&pack.acceptable_words()) {
+                result.insert(x.0, Value::from(x.1));
             }
-            if !flag { continue; }
-            //Red
-            for i in 0..5 {
-                if state[i] == 0 {
-                    if let Some(x) = guess.chars().nth(i) {
-                        if let Some(z) = map.get_mut(&x) {
-                            if *z != 0 { flag = false; break; }
-                        }
-                    }
+            println!("{}", Value::Object(result));
+        },
+        PacksCommand::Openings { name } => {
+            let pack = match packs::find(name) {
+                Some(x) => x,
+                None => {
+                    eprintln!("Unknown pack {}", name);
+                    std::process::exit(1);
                 }
+            };
+            let (final_words, acceptable_words) = (pack.final_words(), pack.acceptable_words());
+            let weights = solver::answer_weights(&final_words, &acceptable_words, 0.0, None);
+            let mut ranking = solver::weighted_guess_scores(&acceptable_words, &weights);
+            let mut result: Map<String, Value> = Map::new();
+            for x in std::iter::from_fn(|| ranking.pop()).take(OPENINGS_SAVED) {
+                result.insert(x.0, Value::from(x.1));
             }
-            if !flag { continue; }
-            if flag {
-                reasonable_words.insert(word.clone());
-            }
-        }
-        entrophy = information_entrophy(&reasonable_words);
-        println!("");
-        chances += 1;
+            println!("{}", Value::Object(result));
+        },
     }
-    println!("You failed! :( The correct answer is {}", answer.to_uppercase());
-    if args.stats {
-        if let Some(ref mut x) = return_value {
-            x.attempt = chances - 1;
-        }
-    }
-    if let Some(ref mut x) = u.games {
-        x.push(game);
+}
+
+///Write the text of an -o option, exit if the file can not be written
+fn write_output(path: &str, text: &str) {
+    if let Err(e) = fs::write(path, text) {
+        eprintln!("Cannot write {}: {}", path, e);
+        std::process::exit(1);
     }
-    let result = to_string_pretty(&u).unwrap();
-    if let Some(path) = &args.state{
-        fs::write(path, result).unwrap();
+}
+
+///Read json files and load into User, exit if the file can not be read or parsed
+fn read_from_file_user(path: &String) -> User{
+    let data = match fs::read_to_string(path) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Cannot read state file {}: {}", path, e);
+            std::process::exit(1);
+        }
+    };
+    match serde_json::from_str(&data) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Invalid state file {}: {}", path, e);
+            std::process::exit(1);
+        }
     }
-    return return_value;
 }
 
-///Test state for one wordle round
-fn game_round_test(args: &Args) -> Option<GameResult> {
+///Day of the answer of a round, only known in random mode
+fn day_of_round(args: &Args) -> Option<usize> {
+    if args.random { Some(args.day) } else { None }
+}
 
-    let mut final_words: BTreeSet<String> = BTreeSet::new();
-    let mut acceptable_words: BTreeSet<String> = BTreeSet::new();
-    let mut final_flag = false;
-    let mut acceptable_flag = false;
-    //导入文件词库
-    if let Some(path) = &args.finalset {
-        final_words = read_to_list(path.clone());
-        final_flag = true;
+///One wordle round on stdin and stdout in the mode of the ui
+fn game_round(ui: Ui, args: &Args, language: &Language) -> io::Result<Option<GameResult>> {
+    //导入词库
+    let (final_words, acceptable_words) = load_word_sets(args);
+    //检查是否是子集
+    if args.finalset.is_some() || args.acceptableset.is_some() {
+        check_word_sets(&final_words, &acceptable_words);
+    }
+    let answer = if args.random {//随机模式启动
+        Some(game::answer_of_day(&answer_pool(args, &final_words, &acceptable_words), args.day, &mut StdRng::seed_from_u64(args.seed)))
     }
     else {
-        for word in FINAL {
-            final_words.insert(word.to_string());
+        args.word.clone()
+    };
+
+    let mut u: User = User { player: None, total_rounds: None, games: None };//Json文件的结构体格式
+    if let Some(path) = &args.state {//需要加载状态Json文件
+        u = read_from_file_user(path);
+        if u.games.is_none() {
+            u.games = Some(Vec::new());
         }
     }
-    if let Some(path) = &args.acceptableset {
-        acceptable_words = read_to_list(path.clone());
-        acceptable_flag = true;
+
+    let weights = answer_weights(args, &final_words, &acceptable_words);
+    let opening = || opening_ranking(args, &acceptable_words, &weights);
+    let rules = Rules {
+        mode: ui.mode(),
+        language,
+        final_words: &final_words,
+        acceptable_words: &acceptable_words,
+        opening: &opening,
+        weights: Some(&weights),
+        difficult: args.difficult,
+        ultra: args.ultra,
+        explain: args.explain,
+        variant: args.variant,
+        theme: Theme::resolve(args.theme),
+        time_limit: args.time_limit,
+        guess_time_limit: args.guess_time_limit,
+        record_times: is_timed(args),
+    };
+    let mut io = Io {
+        input: &mut StdinLines,
+        output: &mut io::stdout(),
+        error: &mut io::stderr(),
+        clock: &SystemClock,
+    };
+    let round = game::play(&rules, &mut io, answer.as_deref())?;
+    if ui.interactive() {
+        println!("{}", share::share_text(day_of_round(args), &round.states, args.difficult, false));
     }
-    else {
-        for word in ACCEPTABLE {
-            acceptable_words.insert(word.to_string());
+    let return_value = record_round(args, &round);
+    //输入在第一次猜测前关闭的游戏不保存，超时的除外
+    let abandoned = round.guesses.is_empty() && !round.timed_out;
+    if let (false, Some(x)) = (abandoned, &mut u.games) {
+        //练习不计入总局数
+        if !args.practice {
+            u.total_rounds = Some(u.total_rounds.unwrap_or(0) + 1);
         }
+        x.push(Game {
+            answer: language::to_upper(&round.answer),
+            guesses: round.guesses,
+            day: day_of_round(args),
+            difficult: args.difficult,
+            times: round.times,
+            timed_out: round.timed_out,
+            hints: round.hints,
+            practice: args.practice,
+            variant: args.variant,
+        });
     }
-    //检查是否是子集
-    if final_flag || acceptable_flag {
-        let mut flag = true;
-        for word in &final_words {
-            if !acceptable_words.contains(word) {
-                flag = false;
-                break;
-            }
-        }
-        if !flag { panic!("Invalid word set--not included."); }
+    if let Some(path) = &args.state {
+        fs::write(path, to_string_pretty(&u).unwrap())?;
     }
+    Ok(return_value)
+}
 
-
-    let mut return_value: Option<GameResult>= None;
-    if args.stats {
-        return_value = Some(GameResult{ win:false, attempt:0, word_list: HashMap::new()});
+///Result of a round for the statistics, only kept with --stats or consecutive rounds
+fn record_round(args: &Args, round: &Round) -> Option<GameResult> {
+    if !args.stats && args.rounds.is_none() {
+        return None;
     }
-
-    let mut answer = String::new();
-    if args.random {//随机模式启动
-        let index = random_spawn(args.day, args.seed, final_words.len());
-        let mut final_words_vec: Vec<&String> = Vec::new();
-        for word in &final_words {
-            final_words_vec.push(word);
+    let mut word_list: HashMap<String, i32> = HashMap::new();
+    if args.stats {
+        for guess in &round.guesses {
+            *word_list.entry(guess.to_lowercase()).or_insert(0) += 1;
         }
-        answer = final_words_vec[index].to_string();
     }
-    else if let Some(ans) = &args.word {
-        answer = ans.to_lowercase().trim().to_string();
+    Some(GameResult {
+        win: round.win,
+        attempt: round.guesses.len() as i32,
+        word_list,
+        times: round.times.clone(),
+        hints: round.hints.clone(),
+    })
+}
+
+///Run automatically and print average attempts
+fn game_round_automatic(args: &Args) {
+    let mut guess_attempt = [0; 7];
+    //导入词库
+    let (final_words, acceptable_words) = load_word_sets(args);
+    let theme = Theme::resolve(args.theme);
+    //内置词库的结果已预先算好
+    if args.finalset.is_none() && args.acceptableset.is_none() && selected_pack(args).name == packs::DEFAULT_PACK {
+        guess_attempt = [94, 0, 40, 525, 1005, 478, 173];
+        print_attempts(&guess_attempt, final_words.len(), theme);
+        return;
     }
-    else {
-        //输入answer
-        io::stdin().read_line(&mut answer).unwrap();
-        answer = answer.to_lowercase().trim().to_string();
+    let opener = match initial_entrophy(args, &acceptable_words).peek() {
+        Some(x) => x.0.clone(),
+        None => String::new(),
+    };
+    for (cnt, answer) in final_words.iter().enumerate() {
+        println!("Processing: {}/{}",cnt, final_words.len());
+        match solver::play_out(answer, &acceptable_words, &opener) {
+            Some(x) => guess_attempt[x] += 1,
+            None => guess_attempt[0] += 1,
+        }
     }
+    print_attempts(&guess_attempt, final_words.len(), theme);
+}
 
+///Print how many answers are solved in each number of attempts and the average
+fn print_attempts(guess_attempt: &[usize; 7], total: usize, theme: Theme) {
+    let share = |x: usize| theme.style('Y').bold().apply_to(format!("{:.2}%", x as f64 / total as f64 * 100.0));
+    println!("{} : {}/{}, {}", theme.bad().apply_to("FAILED"),
+        theme.good().apply_to(guess_attempt[0]), theme.good().apply_to(total), share(guess_attempt[0]));
+    let mut tot = 0;
+    for (i, count) in guess_attempt.iter().enumerate().skip(1) {
+        tot += i * count;
+        println!("{} {} : {}/{}, {}", theme.bad().apply_to(i), theme.bad().apply_to("attempt(s)"),
+            theme.good().apply_to(count), theme.good().apply_to(total), share(*count));
+    }
+    println!("{}: {}", theme.bad().apply_to("Average attempts"),
+        theme.good().apply_to(format!("{:.4}", tot as f64 / total as f64)));
+}
 
-    let mut u: User = User { total_rounds: None, games: None };//Json文件的结构体格式
-    let mut game = Game{answer: answer.to_uppercase().clone(), guesses: Vec::new()};
-    if let Some(path) = &args.state {//需要加载状态Json文件
-        u = read_from_file_user(path);
-        if let Some(ref mut x ) = u.total_rounds {
-            *x += 1;
+///Append the games of an ndjson file to the state file, refusing games that break the rules
+fn import_history(args: &Args, path: &str) {
+    let state = state_path(args);
+    let text = match fs::read_to_string(path) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
         }
-        else {
-            u.total_rounds = Some(1);
+    };
+    let games = match history::parse_ndjson(&text) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("Invalid history {}, {}", path, e);
+            std::process::exit(1);
         }
-        if let None = u.games {
-            u.games = Some(Vec::new());
+    };
+    let mut bad = false;
+    for (index, game) in games.iter().enumerate() {
+        for error in validate::validate_game(game, None) {
+            eprintln!("Invalid imported game {}: {}", index + 1, error);
+            bad = true;
         }
-    }    
-    //字母表映射各字符状态
-    let mut alphabet: HashMap<char, char> = HashMap::new();
-    for c in 'a'..'{' {
-        alphabet.insert(c, 'X');
-    }
-    //困难模式使用的判断
-    let mut yellow_letters: Vec<char> = Vec::new();
-    let mut green_letters= ['0';5];
-    //共六次chances
-    let mut chances = 1;
-    while chances <=6 {
-        
-        //新建answer字符集映射，反映各字母出现次数
-        let mut answer_map: HashMap<char, i32> = HashMap::new();
-        for c in answer.chars() {
-            if let Some(x) = answer_map.get_mut(&c) {
-                *x += 1;
-            }
-            answer_map.entry(c).or_insert(1);
+    }
+    if bad {
+        std::process::exit(1);
+    }
+    let mut u = User { player: None, total_rounds: None, games: None };
+    if fs::metadata(state).is_ok() {
+        if check_state_file(args, state, false) != 0 {
+            eprintln!("Invalid state file, use --repair to drop the bad games");
+            std::process::exit(1);
         }
+        u = read_from_file_user(state);
+    }
+    let count = games.len();
+    //练习不计入总局数
+    let rounds = games.iter().filter(|x| !x.practice).count();
+    u.total_rounds = Some(u.total_rounds.unwrap_or(0) + rounds as i32);
+    u.games.get_or_insert_with(Vec::new).extend(games);
+    fs::write(state, to_string_pretty(&u).unwrap()).unwrap();
+    println!("{} game(s) imported into {}", count, state);
+}
 
-        //输入guess
-        let mut guess = String::new();
-        io::stdin().read_line(&mut guess).unwrap();
-        guess = guess.to_lowercase().trim().to_string();
-        let guess_str = &guess[..];
-        //不合法判断处理
-        if guess.len() != 5 || !ACCEPTABLE.contains(&guess_str) {//长度不合法或不在单词集内
-            println!("INVALID");
-            continue;
-        }
-        else {//非字母
-            let mut flag = true;
-            for c in guess.chars() {
-                if !c.is_alphabetic() {
-                    println!("INVALID");
-                    flag = false;
-                    break;
-                }
-            }
-            if !flag {
-                continue;
-            }
-        }
-        //合法情况
-        //guess的颜色集show
-        let mut show = ['X';5];
-        
-        //非困难模式
-        if !args.difficult {
-            //记录该输入的单词
-            if args.stats {
-                if let Some(ref mut x) = return_value {
-                    let count = x.word_list.entry(guess.clone()).or_insert(0);
-                    *count+=1;
-                }
-            }
-            game.guesses.push(guess.to_uppercase().clone());
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if let Some(y) = answer.chars().nth(i) {
-                        if x == y {//绿色
-                            show[i]='G';
-                            green_letters[i] = x;
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                *z = 'G';
-                            }
-                        }
-                    }
-                }
+///Answers of the practice rounds in the order they are played
+fn practice_words(args: &Args, action: &PracticeCommand) -> Vec<String> {
+    let (final_words, acceptable_words) = load_word_sets(args);
+    match action {
+        PracticeCommand::Day { day } => {
+            let pool = answer_pool(args, &final_words, &acceptable_words);
+            if *day == 0 || *day > pool.len() {
+                eprintln!("Day {} out of range, {} answers in total", day, pool.len());
+                std::process::exit(1);
             }
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if show[i] != 'G' {
-                        if answer_map.get(&x) == Some(&0) || answer_map.get(&x) == None  {//红色
-                            show[i]='R';
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z == 'X' {
-                                    *z = 'R'; 
-                                }
-                            }
-                        }
-                        else {//黄色
-                            show[i]='Y';
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z != 'G' {
-                                    *z = 'Y'; 
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        //困难模式
-        else {
-            //首先增加判断合法条件
-            let mut flag =true;
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if green_letters[i] != '0' && green_letters[i] != x {
-                        println!("INVALID");
-                        flag = false;
-                        break;
-                    }
-                }
-            }
-            if flag {
-                for i in yellow_letters.clone() {
-                    if !guess.contains(i) {
-                            println!("INVALID");
-                            flag = false;
-                            break;
-                    }
+            vec![game::answer_of_day(&pool, *day, &mut StdRng::seed_from_u64(args.seed))]
+        },
+        PracticeCommand::Failed => {
+            //失败过且之后没有猜中的答案，按首次失败的顺序
+            let mut words: Vec<String> = Vec::new();
+            for game in read_from_file_user(state_path(args)).games.unwrap_or_default() {
+                let answer = game.answer.to_lowercase();
+                if game.guesses.last() == Some(&game.answer) {
+                    words.retain(|x| *x != answer);
                 }
-            }
-            if !flag {
-                continue;
-            }
-            if args.stats {
-                if let Some(ref mut x) = return_value {
-                    let count = x.word_list.entry(guess.clone()).or_insert(0);
-                    *count+=1;
+                else if !words.contains(&answer) {
+                    words.push(answer);
                 }
             }
-            game.guesses.push(guess.to_uppercase().clone());
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if let Some(y) = answer.chars().nth(i) {
-                        if x == y {//绿色
-                            show[i] = 'G';
-                            green_letters[i] = x;
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                *z = 'G';
-                            }
-                        }
-                    }
+            words
+        },
+        PracticeCommand::Pattern { pattern, include, exclude, misplaced } => {
+            let query = match Query::parse(Some(pattern), include, exclude, misplaced) {
+                Ok(x) => x,
+                Err(e) => {
+                    eprintln!("Invalid query, {}", e);
+                    std::process::exit(1);
                 }
-            }
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if show[i] != 'G'  {
-                            if answer_map.get(&x) == Some(&0) || answer_map.get(&x) == None {//红色
-                            show[i] = 'R';
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z == 'X' {
-                                    *z = 'R'; 
-                                }
-                            }
-                        }
-                        else {//黄色
-                            show[i] = 'Y';
-                            yellow_letters.push(x);
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                            if let Some(z) = alphabet.get_mut(&x) {
-                                if *z != 'G' {
-                                    *z = 'Y'; 
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+            };
+            let mut words: Vec<String> = query.filter(&final_words).into_iter().collect();
+            words.shuffle(&mut StdRng::seed_from_u64(args.seed));
+            words
+        },
+    }
+}
+
+///Print the best openers with their statistics
+fn print_openers(final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>, sort: RankBy, top: usize, benchmark: bool) {
+    let ranked = openers::rank_openers(acceptable_words, final_words, sort);
+    print!("Rank Opener Entropy Expected Worst");
+    if benchmark {
+        print!(" Average Failed");
+    }
+    println!();
+    for (i, x) in ranked.iter().take(top).enumerate() {
+        print!("{:>4} {:<6} {:>7.4} {:>8.2} {:>5}", i + 1, language::to_upper(&x.word), x.entropy, x.expected, x.worst);
+        if benchmark {
+            let result = openers::benchmark(&x.word, final_words, acceptable_words);
+            print!(" {:>7.4} {:>6}", openers::average_guesses(&result), result[0]);
         }
-        //输出
-        for c in show {
-            print!("{}",c);
+        println!();
+    }
+    println!("{} opener(s) against {} answer(s)", ranked.len(), final_words.len());
+}
+
+///Print every pattern one opener gives with the answers left, the largest first
+fn print_opener_buckets(opener: &str, final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>, theme: Theme) {
+    if !acceptable_words.contains(opener) {
+        eprintln!("{} is not an acceptable word", language::to_upper(opener));
+        std::process::exit(1);
+    }
+    let stats = openers::opener_stats(opener, final_words);
+    println!("Opener {}: entropy {:.4}, expected {:.2}, worst {}, {}/243 patterns possible",
+        theme.good().apply_to(language::to_upper(opener)), stats.entropy, stats.expected, stats.worst, stats.patterns);
+    let mut buckets: Vec<([i32; 5], Vec<String>)> = openers::pattern_buckets(opener, final_words).into_iter().collect();
+    buckets.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(b.0.cmp(&a.0)));
+    for (state, words) in &buckets {
+        let pattern: String = state.iter().map(|x| ['R', 'Y', 'G'][*x as usize]).collect();
+        let shown: Vec<String> = words.iter().take(8).map(|x| language::to_upper(x)).collect();
+        print!("{} {:>5} {}", pattern, words.len(), shown.join(" "));
+        if words.len() > shown.len() {
+            print!(" ...");
         }
-        print!(" ");
-        for c in 'a'..'{' {
-            if let Some(x) = alphabet.get_mut(&c) {
-                print!("{}", x);
-            }
+        println!();
+    }
+}
+
+///Keep the best total time and the fastest guess of a won game
+fn update_best_times(times: &[u64], best_time: &mut Option<u64>, best_guess: &mut Option<u64>) {
+    if times.is_empty() {
+        return;
+    }
+    let total: u64 = times.iter().sum();
+    *best_time = Some(best_time.map_or(total, |x| x.min(total)));
+    if let Some(fastest) = times.iter().min() {
+        *best_guess = Some(best_guess.map_or(*fastest, |x| x.min(*fastest)));
+    }
+}
+
+///Milliseconds shown in seconds
+fn format_time(millis: u64) -> String {
+    format!("{:.2}s", millis as f64 / 1000.0)
+}
+
+///Print the result of each day and the total time of a speedrun
+fn print_speedrun(results: &[(usize, bool, i32, u64)], ui: Ui, theme: Theme) {
+    let wins = results.iter().filter(|x| x.1).count();
+    let total: u64 = results.iter().map(|x| x.3).sum();
+    if ui == Ui::Jsonl {
+        println!("{}", serde_json::json!({"event": "speedrun", "wins": wins, "rounds": results.len(), "time": total}));
+        return;
+    }
+    if !ui.interactive() {//测试模式不输出时间
+        println!("SPEEDRUN {} {}", wins, results.len());
+        return;
+    }
+    println!("{}", theme.good().apply_to("Speedrun finished!"));
+    for (day, win, _, time) in results {
+        let result = if *win { theme.good().apply_to("SOLVED") } else { theme.bad().apply_to("FAILED") };
+        println!("Day {}: {} {}", day, result, format_time(*time));
+    }
+    println!("Solved {}/{} in {}", wins, results.len(), theme.info().apply_to(format_time(total)));
+}
+
+///Create the file of a tournament player, refusing to play the answers again
+fn start_tournament(path: &String, player: &str) {
+    if fs::metadata(path).is_ok() {
+        let u = read_from_file_user(path);
+        if u.games.is_some_and(|x| !x.is_empty()) {
+            eprintln!("{} has already played in {}", player, path);
+            std::process::exit(1);
         }
-        println!("");
-        if guess == answer {//猜词正确
-            println!("CORRECT {}", chances);
-            if args.stats {
-                if let Some(ref mut x) = return_value {
-                    x.win = true;
-                    x.attempt = chances;
-                }
-            }
-            if let Some(ref mut x) = u.games {
-                x.push(game);
+    }
+    let u = User { player: Some(player.to_string()), total_rounds: None, games: None };
+    fs::write(path, to_string_pretty(&u).unwrap()).unwrap();
+}
+
+///Print the result of a player after all tournament rounds
+fn print_tournament(results: &[(usize, bool, i32, u64)], path: &str, ui: Ui, theme: Theme) {
+    let wins = results.iter().filter(|x| x.1).count();
+    let guesses: i32 = results.iter().map(|x| x.2).sum();
+    if ui == Ui::Jsonl {
+        println!("{}", serde_json::json!({"event": "tournament", "wins": wins, "rounds": results.len(), "guesses": guesses}));
+        return;
+    }
+    if !ui.interactive() {
+        println!("TOURNAMENT {} {} {}", wins, results.len(), guesses);
+        return;
+    }
+    println!("Solved {}/{} with {} guesses, results saved to {}",
+        theme.good().apply_to(wins), results.len(),
+        theme.style('Y').bold().apply_to(guesses), path);
+}
+
+///Answers the tournament picks from, exit if there are fewer of them than rounds
+fn tournament_pool(args: &Args, rounds: u64) -> BTreeSet<String> {
+    let (final_words, acceptable_words) = load_word_sets(args);
+    let pool = answer_pool(args, &final_words, &acceptable_words);
+    if rounds as usize > pool.len() {
+        eprintln!("{} rounds out of range, {} answers in total", rounds, pool.len());
+        std::process::exit(1);
+    }
+    pool
+}
+
+///Answers of the tournament days, the same for every player with the seed
+fn tournament_answers(args: &Args, seed: u64, rounds: u64) -> Vec<String> {
+    let pool = tournament_pool(args, rounds);
+    (1..=rounds as usize)
+        .map(|day| language::to_upper(&game::answer_of_day(&pool, day, &mut StdRng::seed_from_u64(seed))))
+        .collect()
+}
+
+///Rank the players by solved count, then by total guesses
+///Players with an invalid game or other answers than the seeded ones are left out
+fn score_tournament(args: &Args, paths: &[String], seed: u64, rounds: u64) {
+    let answers = tournament_answers(args, seed, rounds);
+    let (final_words, acceptable_words) = load_word_sets(args);
+    //选手名、猜中次数、总猜测次数
+    let mut players: Vec<(String, usize, usize)> = Vec::new();
+    for path in paths {
+        let data = match fs::read_to_string(path) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
             }
-            let result = to_string_pretty(&u).unwrap();
-            if let Some(path) = &args.state{
-                fs::write(path, result).unwrap();
+        };
+        let u: User = match serde_json::from_str(&data) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1);
             }
-            return return_value;
+        };
+        let name = u.player.clone().unwrap_or_else(|| {
+            std::path::Path::new(path).file_stem().map_or(path.clone(), |x| x.to_string_lossy().to_string())
+        });
+        //练习的游戏不计入比赛
+        let games: Vec<Game> = u.games.unwrap_or_default().into_iter().filter(|x| !x.practice).collect();
+        let invalid = games.iter().enumerate()
+            .find_map(|(i, x)| validate::validate_game(x, Some((&final_words, &acceptable_words))).into_iter().next().map(|e| (i, e)));
+        if let Some((index, error)) = invalid {
+            eprintln!("Excluded {}: invalid game {}: {}", name, index + 1, error);
+            continue;
         }
-        chances += 1;
+        let played: Vec<String> = games.iter().map(|x| language::to_upper(&x.answer)).collect();
+        if played != answers {
+            eprintln!("Excluded {}: the answers played are not the seeded ones", name);
+            continue;
+        }
+        let solved = games.iter().filter(|x| x.guesses.last() == Some(&x.answer)).count();
+        let guesses = games.iter().map(|x| x.guesses.len()).sum();
+        players.push((name, solved, guesses));
     }
-    //猜词失败
-    println!("FAILED {}", answer.to_uppercase());
-    if args.stats {
-        if let Some(ref mut x) = return_value {
-            x.attempt = chances - 1;
+    players.sort_by(|a, b| b.1.cmp(&a.1).then(a.2.cmp(&b.2)).then(a.0.cmp(&b.0)));
+    println!("Rank Player           Solved Guesses");
+    let mut rank = 0;
+    for i in 0..players.len() {
+        //成绩相同的选手名次相同
+        if i == 0 || players[i].1 != players[i - 1].1 || players[i].2 != players[i - 1].2 {
+            rank = i + 1;
         }
+        println!("{:<4} {:<16} {:>6} {:>7}", rank, players[i].0, players[i].1, players[i].2);
     }
-    if let Some(ref mut x) = u.games {
-        x.push(game);
+}
+
+///Whether guess times are recorded
+fn is_timed(args: &Args) -> bool {
+    args.time_limit.is_some() || args.guess_time_limit.is_some() || args.speedrun.is_some()
+}
+
+///Whether an argument was typed, not taken from its default
+///Global arguments typed after a subcommand are only in the subcommand's matches
+fn given_on_command_line(matches: &ArgMatches, id: &str) -> bool {
+    if matches.try_get_raw(id).is_err() {
+        return false;//子命令中没有这个参数
     }
-    let result = to_string_pretty(&u).unwrap();
-    if let Some(path) = &args.state{
-        fs::write(path, result).unwrap();
+    matches.value_source(id) == Some(ValueSource::CommandLine)
+        || matches.subcommand().is_some_and(|(_, sub)| given_on_command_line(sub, id))
+}
+
+///Id of the argument set by a config key
+fn arg_id(key: &str) -> &str {
+    match key {
+        "final_set" => "finalset",
+        "acceptable_set" => "acceptableset",
+        _ => key,
     }
-    return return_value;
 }
 
-///Run automatically and print average attempts
-fn game_round_automatic(args: &Args) {
-    println!("Do you want to test the {} by the algorithm of {}? {}/{}",
-                console::style("average guess attempts").bold().yellow(),
-                console::style("Information Entrophy").bold().green(),
-                console::style("[Y]").bold().yellow(),
-                console::style("[N]").bold().red()); 
-    let command: char = read!();
-    if command == 'N' { return; }
-    let mut final_words: BTreeSet<String> = BTreeSet::new();
-    let mut acceptable_words: BTreeSet<String> = BTreeSet::new();
-    let mut entrophy: BinaryHeap<WordEntrophy> = BinaryHeap::new();
-    let mut guess_attempt = [0; 7];
-    //导入文件词库
-    if let Some(path) = &args.finalset {
-        final_words = read_to_list(path.clone());
+///Print the words matching a query with their scores
+fn run_query(matches: &BTreeSet<String>, sort: SortBy, frequency: Option<&HashMap<String, f64>>, limit: Option<usize>, json: bool) {
+    let ranked = query::rank(matches, sort, frequency);
+    let shown = &ranked[..limit.unwrap_or(ranked.len()).min(ranked.len())];
+    if json {
+        let words: Vec<Value> = shown.iter().map(|(word, score)| match sort {
+            SortBy::Alpha => serde_json::json!({ "word": word }),
+            SortBy::Entropy => serde_json::json!({ "word": word, "entropy": score }),
+            SortBy::Letters => serde_json::json!({ "word": word, "letters": score }),
+            SortBy::Frequency => serde_json::json!({ "word": word, "frequency": score }),
+        }).collect();
+        println!("{}", to_string_pretty(&serde_json::json!({ "count": matches.len(), "words": words })).unwrap());
+        return;
     }
-    else {
-        for word in FINAL {
-            final_words.insert(word.to_string());
+    for (word, score) in shown {
+        match sort {
+            SortBy::Alpha => println!("{}", language::to_upper(word)),
+            _ => println!("{} {:.4}", language::to_upper(word), score),
         }
     }
-    if let Some(path) = &args.acceptableset {
-        acceptable_words = read_to_list(path.clone());
-    }
-    else {
-        for word in ACCEPTABLE {
-            acceptable_words.insert(word.to_string());
+    println!("{} word(s) found", matches.len());
+}
+
+///Fill the arguments not typed on the command line from the config layers
+fn apply_config(args: &mut Args, c: ConfigFile, cli: impl Fn(&str) -> bool) {
+    if let (false, Some(x)) = (cli("word"), c.word) { args.word = Some(x); }
+    if let (false, Some(x)) = (cli("random"), c.random) { args.random = x; }
+    if let (false, Some(x)) = (cli("day"), c.day) { args.day = x; }
+    if let (false, Some(x)) = (cli("seed"), c.seed) { args.seed = x; }
+    if let (false, Some(x)) = (cli("difficult"), c.difficult) { args.difficult = x; }
+    if let (false, Some(x)) = (cli("ultra"), c.ultra) { args.ultra = x; }
+    if let (false, Some(x)) = (cli("stats"), c.stats) { args.stats = x; }
+    if let (false, Some(x)) = (cli("finalset"), c.final_set) { args.finalset = Some(x); }
+    if let (false, Some(x)) = (cli("acceptableset"), c.acceptable_set) { args.acceptableset = Some(x); }
+    if let (false, Some(x)) = (cli("state"), c.state) { args.state = Some(x); }
+    if let (false, Some(x)) = (cli("language"), c.language) { args.language = Some(x); }
+    if let (false, Some(x)) = (cli("pack"), c.pack) { args.pack = Some(x); }
+    if let (false, Some(x)) = (cli("difficulty"), c.difficulty) { args.difficulty = Some(x); }
+    if let (false, Some(x)) = (cli("time_limit"), c.time_limit) { args.time_limit = Some(x); }
+    if let (false, Some(x)) = (cli("guess_time_limit"), c.guess_time_limit) { args.guess_time_limit = Some(x); }
+    if let (false, Some(x)) = (cli("speedrun"), c.speedrun) { args.speedrun = Some(x.get()); }
+    if let (false, Some(x)) = (cli("repair"), c.repair) { args.repair = x; }
+    if let (false, Some(x)) = (cli("explain"), c.explain) { args.explain = x; }
+    if let (false, Some(x)) = (cli("theme"), c.theme) { args.theme = Some(x); }
+    if let (false, Some(x)) = (cli("ui"), c.ui) { args.ui = Some(x); }
+    if let (false, Some(x)) = (cli("acceptable_weight"), c.acceptable_weight) { args.acceptable_weight = Some(x); }
+    if let (false, Some(x)) = (cli("frequency"), c.frequency) { args.frequency = Some(x); }
+    if let (false, Some(x)) = (cli("variant"), c.variant) { args.variant = x; }
+}
+
+///Print every effective value of the config and the layer it came from
+fn show_config(args: &Args, config: &Config, cli: impl Fn(&str) -> bool) {
+    let effective = ConfigFile {
+        word: args.word.clone(),
+        random: Some(args.random),
+        day: Some(args.day),
+        seed: Some(args.seed),
+        difficult: Some(args.difficult),
+        ultra: Some(args.ultra),
+        stats: Some(args.stats),
+        final_set: args.finalset.clone(),
+        acceptable_set: args.acceptableset.clone(),
+        state: args.state.clone(),
+        language: args.language.clone(),
+        pack: args.pack.clone(),
+        difficulty: args.difficulty,
+        time_limit: args.time_limit,
+        guess_time_limit: args.guess_time_limit,
+        speedrun: args.speedrun.and_then(NonZeroU64::new),
+        repair: Some(args.repair),
+        explain: Some(args.explain),
+        theme: args.theme,
+        ui: args.ui,
+        acceptable_weight: args.acceptable_weight,
+        frequency: args.frequency.clone(),
+        variant: Some(args.variant),
+    };
+    let values = serde_json::to_value(&effective).unwrap();
+    //输出本身就是合法的 toml
+    for key in config::KEYS {
+        let source = if cli(arg_id(key)) { Source::CommandLine } else { config.source(key) };
+        match &values[key] {
+            Value::Null => println!("# {} is not set ({})", key, source),
+            x => println!("{} = {}  # {}", key, x, source),
         }
     }
+}
 
-    let mut tot = 0;
-    if args.finalset.is_none() && args.acceptableset.is_none() {
-        guess_attempt = [111, 0, 38, 473, 818, 615, 260];
-        println!("{} : {}/{}, {:.2}{}", 
-            console::style("FAILED").bold().red(),
-            console::style(guess_attempt[0]).bold().green(),
-            console::style(final_words.len()).bold().green(),
-            console::style((guess_attempt[0] as f64/ final_words.len() as f64) * 100.0).bold().yellow(),
-            console::style("%").bold().yellow(),);
-        for i in 1..7 {
-            tot += i * guess_attempt[i];
-            println!("{} {} : {}/{}, {:.2}{}", 
-            console::style(i).bold().red(),
-            console::style("attempt(s)").bold().red(),
-            console::style(guess_attempt[i]).bold().green(),
-            console::style(final_words.len()).bold().green(),
-            console::style((guess_attempt[i] as f64/ final_words.len() as f64) * 100.0).bold().yellow(),
-            console::style("%").bold().yellow(),);
+/// The main function for the Wordle game
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = Args::command().get_matches();
+    let mut args = match Args::from_arg_matches(&matches) {
+        Ok(x) => x,
+        Err(e) => e.exit(),
+    };
+    let cli = |id: &str| given_on_command_line(&matches, id);
+    let mut config = match Config::load(args.config.as_deref()) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
         }
-        println!("{}: {:.4}",console::style("Average attempts").bold().red(),
-                        console::style(tot as f64 / final_words.len() as f64).bold().green(),);
-        return;
+    };
+    apply_config(&mut args, std::mem::take(&mut config.values), cli);
+    selected_pack(&args);//检查词包名称
+    if let Some(Command::Config { action: ConfigCommand::Show }) = args.command {
+        show_config(&args, &config, cli);
+        return Ok(());
     }
-    let mut cnt = 0;
-    for answer in final_words.clone() {
-        println!("Processing: {}/{}",cnt, final_words.len());
-        cnt += 1;
-        let mut reasonable_words = acceptable_words.clone();//指示针对猜测是否是合法单词集
-        if let Some(path) = &args.acceptableset {
-            acceptable_words = read_to_list(path.clone());
-            entrophy = information_entrophy(&acceptable_words);
-        }
-        else {
-            for word in ACCEPTABLE {
-                acceptable_words.insert(word.to_string());
-            }
-            let config = fs::read_to_string("src/acceptable.json").unwrap();
-            let parsed: Value = serde_json::from_str(&config).unwrap();
-            let obj: Map<String, Value> = parsed.as_object().unwrap().clone();
-            for i in obj {
-                let x: i64 = serde_json::from_value(i.1).unwrap();
-                entrophy.push(WordEntrophy(i.0.clone(), x));
+    //配置文件也可以打开随机模式，所以在合并配置后再检查；子命令有自己的 seed
+    let typed = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    if (typed("day") || typed("seed")) && !args.random {
+        eprintln!("--day and --seed only work in random mode, use --random or set random = true");
+        std::process::exit(1);
+    }
+    let ui = Ui::resolve(args.ui);
+    //纯文本界面默认使用单色主题
+    let theme = match (ui, args.theme) {
+        (Ui::Plain, None) => Theme::Monochrome,
+        _ => Theme::resolve(args.theme),
+    };
+    args.theme = Some(theme);
+    console::set_colors_enabled(ui.colors() && theme != Theme::Monochrome);
+    let language = match &args.language {
+        Some(path) => match Language::load(path) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Invalid language pack, {}", e);
+                std::process::exit(1);
             }
+        },
+        None => Language::english(),
+    };
+    //语言包自带的词库
+    if args.finalset.is_none() { args.finalset = language.final_set.clone(); }
+    if args.acceptableset.is_none() { args.acceptableset = language.acceptable_set.clone(); }
+    if args.language.is_some() {
+        let (final_words, acceptable_words) = load_word_sets(&args);
+        check_alphabet(&final_words, &language);
+        check_alphabet(&acceptable_words, &language);
+    }
+    if args.ultra {
+        args.difficult = true;
+    }
+    //提示和困难模式依赖真实的结果
+    if !args.variant.truthful() && (args.difficult || args.explain) {
+        eprintln!("The {} variant hides the true feedback, it cannot be played with --difficult or --explain", args.variant);
+        std::process::exit(1);
+    }
+    if args.speedrun.is_some() {
+        args.random = true;
+        args.rounds = args.speedrun;
+    }
+    let interactive = ui.interactive();
+    if let Some(Command::Validate { words }) = args.command {
+        let path = state_path(&args);
+        let invalid = check_state_file(&args, path, words);
+        if invalid != 0 {
+            println!("{} invalid game(s) found, use --repair to drop them", invalid);
+            std::process::exit(1);
         }
-
-
-    let mut flag = true;
-    let mut chances = 1;
-    while chances <=6 {
-        //新建answer字符集映射
-        let mut answer_map: HashMap<char, i32> = HashMap::new();
-        for c in answer.chars() {
-            if let Some(x) = answer_map.get_mut(&c) {
-                *x += 1;
+        println!("State file is valid");
+        return Ok(());
+    }
+    if let Some(Command::Tournament { seed, rounds, action: Some(TournamentCommand::Score { paths }), .. }) = &args.command {
+        score_tournament(&args, paths, *seed, *rounds);
+        return Ok(());
+    }
+    if let Some(Command::Tournament { seed, rounds, player, action: None }) = &args.command {
+        let player = match player {
+            Some(x) => x.clone(),
+            None => {
+                eprintln!("No player assigned, use --player to assign one");
+                std::process::exit(1);
             }
-            answer_map.entry(c).or_insert(1);
+        };
+        //在开始任何一局前检查轮数
+        tournament_pool(&args, *rounds);
+        let path = args.state.clone().unwrap_or(format!("{}.json", player));
+        start_tournament(&path, &player);
+        //每位选手从第一天起使用相同的种子
+        args.random = true;
+        args.day = 1;
+        args.seed = *seed;
+        args.rounds = Some(*rounds);
+        args.state = Some(path);
+    }
+    if let Some(Command::Import { path }) = &args.command {
+        import_history(&args, path);
+        return Ok(());
+    }
+    if let Some(path) = &args.state {//校验状态文件
+        if check_state_file(&args, path, false) != 0 {
+            eprintln!("Invalid state file, use --repair to drop the bad games");
+            std::process::exit(1);
         }
-        //输入guess
-        //信息熵提示
-        let mut guess = String::new();
-        if let Some(word) = entrophy.pop(){
-            guess = word.0.clone();
+    }
+    if let Some(Command::Share { index, ascii }) = args.command {
+        let (_, game) = stored_game(&args, index);
+        println!("{}", share::share_text(game.day, &share::game_states(&game), game.difficult, ascii));
+        return Ok(());
+    }
+    if let Some(Command::Replay { index }) = args.command {
+        let (_, game) = stored_game(&args, index);
+        let (_, acceptable_words) = load_word_sets(&args);
+        let ranking = initial_entrophy(&args, &acceptable_words);
+        replay::replay_game(&game, &acceptable_words, ranking, &language, theme, interactive);
+        return Ok(());
+    }
+    if let Some(Command::Analytics { csv, output }) = &args.command {
+        let games = read_from_file_user(state_path(&args)).games.unwrap_or_default();
+        let (_, acceptable_words) = load_word_sets(&args);
+        let opener = match initial_entrophy(&args, &acceptable_words).peek() {
+            Some(x) => x.0.clone(),
+            None => String::new(),
+        };
+        let result = analytics::analyse(&games, &acceptable_words, &opener);
+        let text = if *csv { result.csv() } else { result.text() };
+        match output {
+            Some(path) => {
+                write_output(path, &text);
+                println!("{} game(s) analysed, written to {}", result.games, path);
+            },
+            None => print!("{}", text),
         }
-        if guess == answer {//直接猜出答案
-            guess_attempt[chances] += 1;
-            flag = false;
-            break;
+        return Ok(());
+    }
+    if let Some(Command::Export { format, output }) = &args.command {
+        let games = read_from_file_user(state_path(&args)).games.unwrap_or_default();
+        let (_, acceptable_words) = load_word_sets(&args);
+        let rows = history::export_rows(&games, &acceptable_words);
+        let text = match format {
+            history::Format::Csv => history::to_csv(&rows),
+            history::Format::Ndjson => history::to_ndjson(&rows),
+        };
+        match output {
+            Some(path) => {
+                write_output(path, &text);
+                println!("{} guess(es) of {} game(s) written to {}", rows.len(), games.len(), path);
+            },
+            None => print!("{}", text),
         }
-        let mut show = ['X'; 5];
-        for i in 0..5 {
-            if let Some(x) = guess.chars().nth(i) {
-                    if let Some(y) = answer.chars().nth(i) {
-                        if x == y {
-                            show[i] = 'G';
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                        }
-                    }
-                }
+        return Ok(());
+    }
+    if let Some(Command::Lists { action }) = &args.command {
+        run_lists_command(&args, action);
+        return Ok(());
+    }
+    if let Some(Command::Ratings { csv, output }) = &args.command {
+        let (final_words, acceptable_words) = load_word_sets(&args);
+        let ratings = rate_answers(&args, &final_words, &acceptable_words);
+        let mut text = String::new();
+        if *csv {
+            text += "word,difficulty,score,frequency,repeated,solver_guesses\n";
+            for x in ratings {
+                let frequency = x.frequency.map_or(String::new(), |f| format!("{:.4}", f));
+                text += &format!("{},{},{:.4},{},{},{}\n", x.word, x.difficulty, x.score, frequency, x.repeated, x.solver_guesses);
             }
-            for i in 0..5 {
-                if let Some(x) = guess.chars().nth(i) {
-                    if show[i] != 'G' {
-                        if answer_map.get(&x) == Some(&0) || answer_map.get(&x) == None {
-                            show[i] = 'R';
-                        }
-                        else {
-                            show[i] = 'Y';
-                            if let Some(z) = answer_map.get_mut(&x) {
-                                *z -= 1;
-                            }
-                        }
-                    }
-                }
-            } 
-        //颜色输出结果
-        let mut state = [0; 5];
-        for i in 0..5 {
-            let color = show[i];
-            match color {
-                'G' => { state[i] = 2; },
-                'Y' => { state[i] = 1; },
-                'R' => { state[i] = 0; },
-                 _ => unimplemented!()
+        }
+        else {
+            text += "Word  Difficulty  Score Frequency Repeated Guesses\n";
+            for x in ratings {
+                let frequency = x.frequency.map_or("-".to_string(), |f| format!("{:.4}", f));
+                text += &format!("{} {:<10} {:>6.3} {:>9} {:>8} {:>7}\n", language::to_upper(&x.word), x.difficulty.to_string(),
+                    x.score, frequency, x.repeated, x.solver_guesses);
             }
-        }  
-        //更新信息熵集
-        entrophy.clear();
-        let reasonable_words_copy = reasonable_words.clone();
-        reasonable_words.clear();
-        for word in &reasonable_words_copy {
-            let mut flag = true;
-            let mut target_map: HashMap<char, i32> = HashMap::new();
-            for c in guess.chars() {
-                if let Some(x) = target_map.get_mut(&c) {
-                    *x += 1;
-                }
-                target_map.entry(c).or_insert(1);
+        }
+        match output {
+            Some(path) => {
+                write_output(path, &text);
+                println!("{} rating(s) written to {}", ratings.len(), path);
+            },
+            None => print!("{}", text),
+        }
+        return Ok(());
+    }
+    if let Some(Command::Query { pattern, include, exclude, misplaced, sort, limit, json, path }) = &args.command {
+        let query = match Query::parse(pattern.as_deref(), include, exclude, misplaced) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Invalid query, {}", e);
+                std::process::exit(1);
             }
-            let mut map: HashMap<char, i32> = HashMap::new();
-            for c in word.chars() {
-                if let Some(x) = map.get_mut(&c) {
-                    *x += 1;
-                }
-                map.entry(c).or_insert(1);
+        };
+        let words = match path {
+            Some(x) => read_to_list(x.clone()),
+            None => load_word_sets(&args).1,
+        };
+        let frequency = load_frequency(&args);
+        if *sort == SortBy::Frequency && frequency.is_none() {
+            eprintln!("No frequency file assigned, use --frequency to sort by word frequency");
+            std::process::exit(1);
+        }
+        run_query(&query.filter(&words), *sort, frequency.as_ref(), *limit, *json);
+        return Ok(());
+    }
+    if let Some(Command::Openers { opener, sort, top, benchmark }) = &args.command {
+        let (final_words, acceptable_words) = load_word_sets(&args);
+        if args.finalset.is_some() || args.acceptableset.is_some() {
+            check_word_sets(&final_words, &acceptable_words);
+        }
+        match opener {
+            Some(x) => print_opener_buckets(&language::normalize(x), &final_words, &acceptable_words, theme),
+            None => print_openers(&final_words, &acceptable_words, *sort, *top, *benchmark),
+        }
+        return Ok(());
+    }
+    if let Some(Command::Benchmark) = args.command {
+        game_round_automatic(&args);
+        return Ok(());
+    }
+    if let Some(Command::Packs { action }) = &args.command {
+        run_packs_command(action);
+        return Ok(());
+    }
+    if let Some(Command::Verify { day, seed, limit }) = args.command {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        let (header_day, rows) = match verify::parse_grid(&text) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Invalid grid, {}", e);
+                std::process::exit(1);
             }
-            //Green
-            for i in 0..5 {
-                if state[i] == 2 && word.chars().nth(i) != guess.chars().nth(i) {
-                    flag = false;
-                    break;
-                }
-                else if state[i] == 2 && word.chars().nth(i) == guess.chars().nth(i) {
-                    if let Some(x) = word.chars().nth(i) {
-                        if let Some(z) = target_map.get_mut(&x) {
-                            *z -= 1;
-                        }
-                        if let Some(z) = map.get_mut(&x) {
-                            *z -= 1;
-                        }
-                    }
-                }
+        };
+        let (final_words, acceptable_words) = load_word_sets(&args);
+        let final_words = answer_pool(&args, &final_words, &acceptable_words);
+        let day = match day.or(header_day) {
+            Some(x) if x >= 1 && x <= final_words.len() => x,
+            Some(x) => {
+                eprintln!("Day {} out of range, {} answers in total", x, final_words.len());
+                std::process::exit(1);
             }
-            if !flag { continue; }
-            //Yellow
-            for i in 0..5 {
-                if state[i] == 1 {
-                    if word.chars().nth(i) == guess.chars().nth(i) { flag = false; break; }
-                    else if let Some(x) = guess.chars().nth(i) {
-                        if let Some(z) = map.get_mut(&x) {
-                            if *z == 0 { flag = false; break; }
-                            else { *z -= 1; }
-                        }
-                        else { flag = false; break; }
-                    }
-                }
+            None => {
+                eprintln!("No day assigned and none found in the grid header");
+                std::process::exit(1);
             }
-            if !flag { continue; }
-            //Red
-            for i in 0..5 {
-                if state[i] == 0 {
-                    if let Some(x) = guess.chars().nth(i) {
-                        if let Some(z) = map.get_mut(&x) {
-                            if *z != 0 { flag = false; break; }
-                        }
-                    }
-                }
+        };
+        let answer = game::answer_of_day(&final_words, day, &mut StdRng::seed_from_u64(seed));
+        println!("Answer of day {}: {}", day, language::to_upper(&answer));
+        let mut consistent = true;
+        for (i, state) in rows.iter().enumerate() {
+            let row = share::row_text(state, false);
+            if i > 0 && rows[i - 1] == [2; 5] {
+                println!("Row {} {}: the game was already won", i + 1, row);
+                consistent = false;
+                continue;
+            }
+            let guesses = verify::possible_guesses(&acceptable_words, &answer, state);
+            if guesses.is_empty() {
+                println!("Row {} {}: no acceptable guess gives this row", i + 1, row);
+                consistent = false;
+                continue;
             }
-            if !flag { continue; }
-            if flag {
-                reasonable_words.insert(word.clone());
+            let shown: Vec<String> = guesses.iter().take(limit).map(|x| language::to_upper(x)).collect();
+            print!("Row {} {}: {} possible guess(es) {}", i + 1, row, guesses.len(), shown.join(" "));
+            if guesses.len() > limit {
+                print!(" ...");
             }
+            println!();
         }
-        entrophy = information_entrophy(&reasonable_words);
-        chances += 1;       
-    }
-    if flag { guess_attempt[0] += 1; }
-    }
-    println!("{} : {}/{}, {:.2}{}", 
-            console::style("FAILED").bold().red(),
-            console::style(guess_attempt[0]).bold().green(),
-            console::style(final_words.len()).bold().green(),
-            console::style((guess_attempt[0] as f64/ final_words.len() as f64) * 100.0).bold().yellow(),
-            console::style("%").bold().yellow(),);
-        for i in 1..7 {
-            tot += i * guess_attempt[i];
-            println!("{} {} : {}/{}, {:.2}{}", 
-            console::style(i).bold().red(),
-            console::style("attempt(s)").bold().red(),
-            console::style(guess_attempt[i]).bold().green(),
-            console::style(final_words.len()).bold().green(),
-            console::style((guess_attempt[i] as f64/ final_words.len() as f64) * 100.0).bold().yellow(),
-            console::style("%").bold().yellow(),);
+        if rows.len() > validate::MAX_GUESSES {
+            println!("{} rows given, at most {} allowed", rows.len(), validate::MAX_GUESSES);
+            consistent = false;
         }
-    println!("{}: {:.4}",console::style("Average attempts").bold().red(),
-                    console::style(tot as f64 / final_words.len() as f64).bold().green(),);
-}
-
-//One wordle round
-fn game_round(flag: bool, args:&Args) -> Option<GameResult> {
-    if let true = flag {
-        game_round_normal(&args)
+        if consistent {
+            println!("The grid is consistent with the answer");
+        }
+        else {
+            println!("The grid is NOT consistent with the answer");
+            std::process::exit(1);
+        }
+        return Ok(());
     }
-    else {
-        game_round_test(&args)
+    //练习模式依次使用这些答案
+    let mut practice: Vec<String> = Vec::new();
+    if let Some(Command::Practice { action }) = &args.command {
+        practice = practice_words(&args, action);
+        if practice.is_empty() {
+            println!("Nothing to practice");
+            return Ok(());
+        }
+        args.practice = true;
+        args.random = false;
+        args.rounds = None;
+        args.speedrun = None;
     }
-}
-
-
-/// The main function for the Wordle game
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut args = Args::parse();
-    if let Some(path) = &args.config {
-        let args_config = read_from_file_config(path);
-        if let None = args.word { args.word = args_config.word; }
-        if let false = args.random { args.random = args_config.random; }
-        if let 1 = args.day { args.day = args_config.day; }
-        if let 114514 = args.seed { args.seed = args_config.seed; }
-        if let false = args.difficult { args.difficult = args_config.difficult; }
-        if let false = args.stats { args.stats = args_config.stats; }
-        if let None = args.finalset { args.finalset = args_config.finalset; }
-        if let None = args.acceptableset { args.acceptableset = args_config.acceptableset; }
-        if let None = args.state { args.state = args_config.state; }
-    }
-    let is_tty = atty::is(atty::Stream::Stdout);
-    if is_tty {
+    if interactive {
         println!(
             "I am in a tty. Please print {}!",
-            console::style("colorful characters").bold().blink().blue()
+            theme.info().blink().apply_to("colorful characters")
         );
-        print!("{}", console::style("Your name: ").bold().red());
+        print!("{}", theme.bad().apply_to("Your name: "));
         io::stdout().flush().unwrap();
     
         let mut line = String::new();
         io::stdin().read_line(&mut line)?;
         println!("Welcome to wordle, {}!", line.trim());
+        if args.language.is_some() {
+            println!("Language: {}", theme.info().apply_to(&language.name));
+        }
     
     }
-    if is_tty { game_round_automatic(&args); }
     //记录测试信息的数据
     let mut win_round = 0;
     let mut lose_round = 0;
     let mut tot_attempt = 0;
     let mut average: f64;
     let mut words_dict: HashMap<String, i32> = HashMap::new();
+    //计时游戏的最好成绩，单位为毫秒
+    let mut best_time: Option<u64> = None;
+    let mut fastest_guess: Option<u64> = None;
+    //提示次数与总代价
+    let mut hint_count = 0;
+    let mut hint_penalty = 0;
+    //当前与最长连胜，不含练习
+    let mut streak = 0;
+    let mut best_streak = 0;
+    //连续进行时每天的结果：日期、是否猜中、猜测次数、用时
+    let mut round_results: Vec<(usize, bool, i32, u64)> = Vec::new();
     if let Some(path) = &args.state {//加载前几轮信息
         let u = read_from_file_user(path);
-        for x in &u.games {
-            for round in x{
-                if round.answer == round.guesses[round.guesses.len() - 1] {
+        if let Some(x) = &u.games {
+            for round in x.iter().filter(|x| !x.practice) {
+                if round.guesses.last() == Some(&round.answer) {
                     win_round += 1;
+                    streak += 1;
+                    best_streak = std::cmp::max(best_streak, streak);
                     tot_attempt += round.guesses.len() as i32;
+                    update_best_times(&round.times, &mut best_time, &mut fastest_guess);
                 }
                 else {
                     lose_round += 1;
+                    streak = 0;
                 }
+                hint_count += round.hints.len();
+                hint_penalty += round.hints.iter().map(|x| *x as u32).sum::<u32>();
                 for word in &round.guesses {
                     let count = words_dict.entry(word.clone()).or_insert(0);
                     *count += 1;
@@ -1318,31 +1690,52 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
     loop{
-        let gameresult = game_round(is_tty, &args);
+        if args.practice {
+            args.word = Some(practice.remove(0));
+        }
+        let gameresult = game_round(ui, &args, &language)?;
         
-        if args.stats {//输出测试信息
+        if args.stats && !args.practice {//输出测试信息，练习不计入
             if let Some(ref x)=gameresult {
                 if x.win {
                     win_round += 1;
                     tot_attempt += x.attempt;
+                    update_best_times(&x.times, &mut best_time, &mut fastest_guess);
+                    streak += 1;
+                    best_streak = std::cmp::max(best_streak, streak);
                 }
                 else{
                     lose_round += 1;
+                    streak = 0;
                 }
+                hint_count += x.hints.len();
+                hint_penalty += x.hints.iter().map(|x| *x as u32).sum::<u32>();
                 if win_round == 0 {
                     average = 0.0;
                 }
                 else {
                     average = ( tot_attempt as f64 ) / ( win_round as f64 );
                 }
-                if !is_tty {//测试模式按要求输出
+                if ui == Ui::Test {//测试模式按要求输出
                     print!("{} {} {:.2}", win_round, lose_round, average);
-                    println!("");
+                    println!();
                 }
-                else {//交互模式
+                else if interactive {//交互模式
                     println!("Game Statistics:");
-                    println!("Win rate: {:.2}", console::style(win_round as f64 / (win_round + lose_round) as f64).bold().green());
-                    println!("Average attempts of wins: {:.2}", console::style(average).bold().cyan());
+                    println!("Win rate: {}", theme.good().apply_to(format!("{:.2}", win_round as f64 / (win_round + lose_round) as f64)));
+                    println!("Average attempts of wins: {:.2}", theme.info().apply_to(average));
+                    println!("Current streak: {} Best streak: {}", theme.info().apply_to(streak),
+                        theme.info().apply_to(best_streak));
+                    if let Some(x) = best_time {
+                        println!("Best time: {}", theme.info().apply_to(format_time(x)));
+                    }
+                    if let Some(x) = fastest_guess {
+                        println!("Fastest guess: {}", theme.info().apply_to(format_time(x)));
+                    }
+                    if hint_count > 0 {
+                        println!("Hints used: {} Penalty: {}", theme.info().apply_to(hint_count),
+                            theme.bad().apply_to(hint_penalty));
+                    }
                 }
                 let mut words_heap: BinaryHeap<WordDict> = BinaryHeap::new();
                 for (key, value) in &x.word_list {
@@ -1352,55 +1745,77 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 for (key, value) in &words_dict {
                     words_heap.push(WordDict(key.to_uppercase().clone(),*value));
                 }
-                let mut i = 0;
-                if is_tty {
-                    println!("Your preferred words: ");
+                if ui == Ui::Jsonl {//常用词一并输出
+                    let words: Vec<(String, i32)> = std::iter::from_fn(|| words_heap.pop()).take(5).map(|x| (x.0, x.1)).collect();
+                    println!("{}", serde_json::json!({"event": "stats", "wins": win_round, "losses": lose_round,
+                        "average": (average * 100.0).round() / 100.0, "hints": hint_count, "penalty": hint_penalty, "words": words}));
                 }
-                while !words_heap.is_empty() && i < 5 {
-                    if let Some(x) = words_heap.pop(){
-                        if !is_tty {//测试模式
-                            print!("{} {}",x.0.to_uppercase(), x.1);
+                else {
+                    let mut i = 0;
+                    if interactive {
+                        println!("Your preferred words: ");
+                    }
+                    while !words_heap.is_empty() && i < 5 {
+                        if let Some(x) = words_heap.pop(){
+                            if ui == Ui::Test {//测试模式
+                                print!("{} {}",x.0.to_uppercase(), x.1);
+                            }
+                            else {//交互模式
+                                println!("{}---{} time(s)",
+                                        theme.good().apply_to(x.0.to_uppercase()),
+                                        theme.info().apply_to(x.1))
+                            }
                         }
-                        else {//交互模式
-                            println!("{}---{} time(s)",
-                                    console::style(x.0.to_uppercase()).bold().magenta(),
-                                    console::style(x.1).bold().blue())
+                        i += 1;
+                        if !words_heap.is_empty() && i < 5 && ui == Ui::Test {
+                            print!(" ");
                         }
                     }
-                    i += 1;
-                    if !words_heap.is_empty() && i < 5 && !is_tty {
-                        print!(" ");
-                    }
+                    if ui == Ui::Test { println!(); }
                 }
-                if !is_tty { println!(""); }
             } 
         }
-        if args.word == None {//没有使用 -w/--word 参数指定答案
-            if !is_tty {//测试模式
-                let command: char = read!();
+        if let Some(n) = args.rounds {//竞速与锦标赛模式连续进行
+            if let Some(ref x) = gameresult {
+                round_results.push((args.day, x.win, x.attempt, x.times.iter().sum()));
+            }
+            if (round_results.len() as u64) < n {
+                args.day += 1;
+                continue;
+            }
+            if args.speedrun.is_some() {
+                print_speedrun(&round_results, ui, theme);
+            }
+            else {
+                print_tournament(&round_results, state_path(&args), ui, theme);
+            }
+            break;
+        }
+        if args.word.is_none() || !practice.is_empty() {//没有使用 -w/--word 参数指定答案，或还有要练习的单词
+            if !interactive {//测试模式
+                let command = StdinLines.read_char(None).unwrap_or('N');
                 if command == 'N' {
                     break;
                 }
-                else if command == 'Y' {
-                    if args.random {
+                else if command == 'Y'
+                    && args.random {
                         args.day += 1;
-                    }  
-                }
+                    }
             }
             else {//交互模式
                 print!("Would you like to start another round? {}/{} ",
-                        console::style("[Y]").bold().yellow(),
-                        console::style("[N]").bold().red());
-                let command: char = read!();
+                        theme.style('Y').bold().apply_to("[Y]"),
+                        theme.bad().apply_to("[N]"));
+                io::stdout().flush().unwrap();
+                let command = StdinLines.read_char(None).unwrap_or('N');
                 if command == 'N' {
-                    println!("{}",console::style("Thanks for playing!").bold().blink().color256(114));
+                    println!("{}",theme.good().blink().apply_to("Thanks for playing!"));
                     break;
                 }
-                else if command == 'Y' {
-                    if args.random {
+                else if command == 'Y'
+                    && args.random {
                         args.day += 1;
-                    }  
-                }
+                    }
             }
         }
         else {
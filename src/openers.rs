@@ -0,0 +1,87 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::feedback::compute_state;
+use crate::solver::{bucket_entrophy, pattern_counts, play_out};
+
+///How well one first guess splits the answers
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenerStats {
+    pub word: String,
+    ///Information entrophy of the patterns in bits
+    pub entropy: f64,
+    ///Candidates expected to be left after the guess, the answer included
+    pub expected: f64,
+    ///Candidates left in the largest pattern bucket
+    pub worst: usize,
+    ///Patterns out of the 243 that some answer gives
+    pub patterns: usize,
+}
+
+///Which statistic ranks the openers
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RankBy {
+    ///The most entrophy first
+    Entropy,
+    ///The fewest expected candidates first
+    Expected,
+    ///The smallest worst bucket first
+    Worst,
+}
+
+///Answers grouped by the pattern the guess gives for them
+///2->Green 1->Yellow 0->Red
+pub fn pattern_buckets(guess: &str, answers: &BTreeSet<String>) -> BTreeMap<[i32; 5], Vec<String>> {
+    let mut buckets: BTreeMap<[i32; 5], Vec<String>> = BTreeMap::new();
+    for answer in answers {
+        buckets.entry(compute_state(guess, answer)).or_default().push(answer.clone());
+    }
+    buckets
+}
+
+///Statistics of one guess against the answers, the entrophy being the solver's own
+pub fn opener_stats(guess: &str, answers: &BTreeSet<String>) -> OpenerStats {
+    //只统计每种结果的数量，不保存单词
+    let counts = pattern_counts(guess, answers);
+    let total = answers.len() as f64;
+    let expected = counts.iter().map(|x| *x as f64 * *x as f64 / total).sum();
+    OpenerStats {
+        word: guess.to_string(),
+        entropy: bucket_entrophy(&counts.map(|x| x as f64), total),
+        expected,
+        worst: counts.iter().copied().max().unwrap_or(0),
+        patterns: counts.iter().filter(|x| **x > 0).count(),
+    }
+}
+
+///Statistics of every guess, the best first
+pub fn rank_openers(guesses: &BTreeSet<String>, answers: &BTreeSet<String>, rank_by: RankBy) -> Vec<OpenerStats> {
+    let mut result: Vec<OpenerStats> = guesses.iter().map(|x| opener_stats(x, answers)).collect();
+    result.sort_by(|a, b| {
+        let order = match rank_by {
+            RankBy::Entropy => b.entropy.partial_cmp(&a.entropy).unwrap(),
+            RankBy::Expected => a.expected.partial_cmp(&b.expected).unwrap(),
+            RankBy::Worst => a.worst.cmp(&b.worst).then(b.entropy.partial_cmp(&a.entropy).unwrap()),
+        };
+        order.then(a.word.cmp(&b.word))
+    });
+    result
+}
+
+///Play every answer with the solver starting from opener
+///Index 0 counts the failed games, index i the games solved in i guesses
+pub fn benchmark(opener: &str, final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>) -> [usize; 7] {
+    let mut result = [0; 7];
+    for answer in final_words {
+        result[play_out(answer, acceptable_words, opener).unwrap_or(0)] += 1;
+    }
+    result
+}
+
+///Average guesses of the solved games in a benchmark
+pub fn average_guesses(result: &[usize; 7]) -> f64 {
+    let solved: usize = result[1..].iter().sum();
+    if solved == 0 {
+        return 0.0;
+    }
+    let total: usize = result.iter().enumerate().skip(1).map(|(i, x)| i * x).sum();
+    total as f64 / solved as f64
+}
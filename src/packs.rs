@@ -0,0 +1,107 @@
+use std::collections::{BTreeSet, BinaryHeap};
+use serde_json::{Map, Value};
+use crate::builtin_words::{FINAL, ACCEPTABLE, REMOVED_2022, KIDS, FOOD};
+use crate::WordEntrophy;
+
+///A word pack compiled into the program
+pub struct Pack {
+    pub name: &'static str,
+    pub description: &'static str,
+    final_set: &'static [&'static str],
+    ///Words taken out of the final set
+    removed: &'static [&'static str],
+    acceptable_set: &'static [&'static str],
+    ///Information entrophy of the acceptable words, generated by `packs entrophy`
+    entrophy: &'static str,
+    ///Best first guesses for the hints with the final words as answers, generated by `packs openings`
+    openings: &'static str,
+}
+
+const NYT_ENTROPHY: &str = include_str!("acceptable.json");
+const KIDS_ENTROPHY: &str = include_str!("kids.json");
+const NYT_OPENINGS: &str = include_str!("nyt_openings.json");
+const NYT_2022_OPENINGS: &str = include_str!("nyt_2022_openings.json");
+const KIDS_OPENINGS: &str = include_str!("kids_openings.json");
+const FOOD_OPENINGS: &str = include_str!("food_openings.json");
+
+pub const DEFAULT_PACK: &str = "nyt";
+
+pub const PACKS: &[Pack] = &[
+    Pack {
+        name: "nyt",
+        description: "The original NYT answers and guesses",
+        final_set: FINAL,
+        removed: &[],
+        acceptable_set: ACCEPTABLE,
+        entrophy: NYT_ENTROPHY,
+        openings: NYT_OPENINGS,
+    },
+    Pack {
+        name: "nyt-2022",
+        description: "The NYT answers after the 2022 edits",
+        final_set: FINAL,
+        removed: REMOVED_2022,
+        acceptable_set: ACCEPTABLE,
+        entrophy: NYT_ENTROPHY,
+        openings: NYT_2022_OPENINGS,
+    },
+    Pack {
+        name: "kids",
+        description: "Easy answers, only common words accepted as guesses",
+        final_set: KIDS,
+        removed: &[],
+        acceptable_set: FINAL,
+        entrophy: KIDS_ENTROPHY,
+        openings: KIDS_OPENINGS,
+    },
+    Pack {
+        name: "food",
+        description: "Themed answers about food",
+        final_set: FOOD,
+        removed: &[],
+        acceptable_set: ACCEPTABLE,
+        entrophy: NYT_ENTROPHY,
+        openings: FOOD_OPENINGS,
+    },
+];
+
+///Find a pack by its name
+pub fn find(name: &str) -> Option<&'static Pack> {
+    PACKS.iter().find(|x| x.name == name)
+}
+
+impl Pack {
+    pub fn final_words(&self) -> BTreeSet<String> {
+        self.final_set
+            .iter()
+            .filter(|x| !self.removed.contains(x))
+            .map(|x| x.to_string())
+            .collect()
+    }
+
+    pub fn acceptable_words(&self) -> BTreeSet<String> {
+        self.acceptable_set.iter().map(|x| x.to_string()).collect()
+    }
+
+    ///Load the precomputed information entrophy of the acceptable words
+    pub fn entrophy(&self) -> BinaryHeap<WordEntrophy> {
+        parse_ranking(self.entrophy)
+    }
+
+    ///Load the precomputed best first guesses, scored by solver::weighted_guess_score
+    pub fn openings(&self) -> BinaryHeap<WordEntrophy> {
+        parse_ranking(self.openings)
+    }
+}
+
+///Ranking saved as a json object of words and their values
+fn parse_ranking(text: &str) -> BinaryHeap<WordEntrophy> {
+    let mut result: BinaryHeap<WordEntrophy> = BinaryHeap::new();
+    let parsed: Value = serde_json::from_str(text).unwrap();
+    let obj: Map<String, Value> = parsed.as_object().unwrap().clone();
+    for i in obj {
+        let x: i64 = serde_json::from_value(i.1).unwrap();
+        result.push(WordEntrophy(i.0.clone(), x));
+    }
+    result
+}
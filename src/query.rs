@@ -0,0 +1,171 @@
+use std::collections::{BTreeSet, HashMap};
+use crate::language;
+use crate::solver::{filter_words, guess_entrophy};
+
+///Letters known about the answer, like the colors of the guesses so far
+///Patterns have 5 letters with `_`, `.` or `?` for unknown positions
+///The words are matched by the solver's own filter, the query standing for guesses and their colors
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Query {
+    ///Green letters at their positions
+    pub pattern: [Option<char>; 5],
+    ///Letters somewhere in the word, repeated for more copies
+    pub include: Vec<char>,
+    ///Letters not in the word at all
+    pub exclude: Vec<char>,
+    ///Yellow letters: in the word but not at this position
+    pub misplaced: Vec<(char, usize)>,
+}
+
+///How the matching words are ordered
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    ///In alphabetical order
+    Alpha,
+    ///The guess splitting the other matches best first, in bits
+    Entropy,
+    ///The word with the most common letter at each position among the matches first
+    Letters,
+    ///The most frequent word first, counts taken from the --frequency file
+    Frequency,
+}
+
+///Filler of the guesses built from a query, not a letter of any word
+const UNKNOWN: char = '_';
+
+///A guess with letter at the positions and the filler elsewhere
+fn guess_with(letter: char, positions: &[usize]) -> String {
+    (0..5).map(|i| if positions.contains(&i) { letter } else { UNKNOWN }).collect()
+}
+
+///A state with color at the positions and red elsewhere, red filler tells nothing
+fn state_at(positions: &[usize], color: i32) -> [i32; 5] {
+    let mut state = [0; 5];
+    for i in positions {
+        state[*i] = color;
+    }
+    state
+}
+
+///Every choice of count positions out of 5
+fn position_sets(count: usize) -> Vec<Vec<usize>> {
+    (0u32..32)
+        .filter(|x| x.count_ones() as usize == count)
+        .map(|x| (0..5).filter(|i| x & (1 << i) != 0).collect())
+        .collect()
+}
+
+///Letters of one 5-letter pattern, None for unknown positions
+fn parse_pattern(pattern: &str) -> Result<[Option<char>; 5], String> {
+    let letters: Vec<char> = language::normalize(pattern).chars().collect();
+    if letters.len() != 5 {
+        return Err(format!("pattern {} should have 5 letters", pattern));
+    }
+    let mut result = [None; 5];
+    for (i, c) in letters.into_iter().enumerate() {
+        if !matches!(c, '_' | '.' | '?') {
+            result[i] = Some(c);
+        }
+    }
+    Ok(result)
+}
+
+impl Query {
+    ///Build a query from the command line strings, each misplaced one being a pattern of yellow letters
+    pub fn parse(pattern: Option<&str>, include: &str, exclude: &str, misplaced: &[String]) -> Result<Query, String> {
+        let mut query = Query {
+            pattern: match pattern {
+                Some(x) => parse_pattern(x)?,
+                None => [None; 5],
+            },
+            include: language::normalize(include).chars().collect(),
+            exclude: language::normalize(exclude).chars().collect(),
+            misplaced: Vec::new(),
+        };
+        for x in misplaced {
+            for (i, c) in parse_pattern(x)?.iter().enumerate() {
+                if let Some(c) = c {
+                    query.misplaced.push((*c, i));
+                }
+            }
+        }
+        let required: Vec<char> = query.pattern.iter().flatten()
+            .chain(&query.include)
+            .chain(query.misplaced.iter().map(|x| &x.0))
+            .copied()
+            .collect();
+        if let Some(c) = query.exclude.iter().find(|c| required.contains(c)) {
+            return Err(format!("letter {} is both required and excluded", language::upper_char(*c)));
+        }
+        if let Some((c, i)) = query.misplaced.iter().find(|(c, i)| query.pattern[*i] == Some(*c)) {
+            return Err(format!("letter {} is both correct and misplaced at position {}", language::upper_char(*c), i + 1));
+        }
+        Ok(query)
+    }
+
+    ///The query as guesses with the colors every matching word gives them
+    ///Included letters have no position and are not among them
+    pub fn constraints(&self) -> Vec<(String, [i32; 5])> {
+        let mut result = Vec::new();
+        let greens: Vec<usize> = (0..5).filter(|i| self.pattern[*i].is_some()).collect();
+        if !greens.is_empty() {
+            let guess = self.pattern.iter().map(|x| x.unwrap_or(UNKNOWN)).collect();
+            result.push((guess, state_at(&greens, 2)));
+        }
+        for (c, i) in &self.misplaced {
+            result.push((guess_with(*c, &[*i]), state_at(&[*i], 1)));
+        }
+        for c in &self.exclude {
+            result.push((guess_with(*c, &[0]), [0; 5]));
+        }
+        result
+    }
+
+    ///Keep the words fitting the query
+    pub fn filter(&self, words: &BTreeSet<String>) -> BTreeSet<String> {
+        let mut result = words.clone();
+        for (guess, state) in self.constraints() {
+            result = filter_words(&result, &guess, &state);
+        }
+        //包含的字母按出现次数计算：任选该数目的位置为绿色，结果取并集
+        let mut needed: HashMap<char, usize> = HashMap::new();
+        for c in &self.include {
+            *needed.entry(*c).or_insert(0) += 1;
+        }
+        for (c, n) in needed {
+            result = position_sets(n).iter()
+                .flat_map(|x| filter_words(&result, &guess_with(c, x), &state_at(x, 2)))
+                .collect();
+        }
+        result
+    }
+}
+
+///Score every word for the order, the best first
+///Entropy is in bits over the words themselves, letters is the average share of the words
+///with the same letter at each position, 0~1, frequency is the count in the table, 0 if missing
+pub fn rank(words: &BTreeSet<String>, sort: SortBy, frequency: Option<&HashMap<String, f64>>) -> Vec<(String, f64)> {
+    let mut result: Vec<(String, f64)> = match sort {
+        SortBy::Alpha => words.iter().map(|x| (x.clone(), 0.0)).collect(),
+        SortBy::Entropy => words.iter().map(|x| (x.clone(), guess_entrophy(x, words))).collect(),
+        SortBy::Frequency => words.iter()
+            .map(|x| (x.clone(), frequency.and_then(|f| f.get(x)).copied().unwrap_or(0.0)))
+            .collect(),
+        SortBy::Letters => {
+            let mut positions: HashMap<(usize, char), usize> = HashMap::new();
+            for word in words {
+                for (i, c) in word.chars().enumerate() {
+                    *positions.entry((i, c)).or_insert(0) += 1;
+                }
+            }
+            words.iter().map(|word| {
+                let total: usize = word.chars().enumerate().map(|(i, c)| positions[&(i, c)]).sum();
+                (word.clone(), total as f64 / 5.0 / words.len() as f64)
+            }).collect()
+        },
+    };
+    if sort != SortBy::Alpha {
+        result.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(&b.0)));
+    }
+    result
+}
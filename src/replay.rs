@@ -0,0 +1,125 @@
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use std::io::{self, Write};
+use crate::language::{self, Language};
+use crate::theme::Theme;
+use wordle::solver::{filter_words, guess_entrophy, information_entrophy, WordEntrophy};
+use wordle::game::{print_keyboard, print_row};
+use crate::{compute_state, Game};
+
+///Skill of a guess: its entrophy against the solver's best guess, 0~100
+pub fn skill_score(guess_bits: f64, best_bits: f64, in_candidates: bool) -> i32 {
+    if best_bits <= 0.0 {
+        //只剩一个候选词时，猜中即满分
+        return if in_candidates { 100 } else { 0 };
+    }
+    ((guess_bits / best_bits) * 100.0).round().clamp(0.0, 100.0) as i32
+}
+
+///Luck of a guess: share of the possible outcomes that would have left more candidates, 0~100
+pub fn luck_score(guess: &str, answer: &str, candidates: &BTreeSet<String>) -> i32 {
+    if candidates.len() <= 1 {
+        return 50;
+    }
+    let mut buckets: HashMap<[i32; 5], usize> = HashMap::new();
+    for word in candidates {
+        *buckets.entry(compute_state(guess, word)).or_insert(0) += 1;
+    }
+    let actual = buckets.get(&compute_state(guess, answer)).copied().unwrap_or(0);
+    let mut score = 0.0;
+    for word in candidates {
+        let size = buckets[&compute_state(guess, word)];
+        if size > actual {
+            score += 1.0;
+        }
+        else if size == actual {
+            score += 0.5;
+        }
+    }
+    (score / candidates.len() as f64 * 100.0).round() as i32
+}
+
+///Entrophy of the guess in bits, looked up in the ranking if possible
+fn bits_of(guess: &str, ranking: &BinaryHeap<WordEntrophy>, candidates: &BTreeSet<String>) -> f64 {
+    match ranking.iter().find(|x| x.0 == guess) {
+        Some(x) => x.1 as f64 / 1000000000.0,
+        None => guess_entrophy(guess, candidates),
+    }
+}
+
+///Step through one stored game guess by guess
+///The rows and keyboard are shown as the variant of the game showed them, the analysis uses the true feedback
+///The first ranking is the entrophy of the acceptable words, later ones are computed on the candidates left
+pub fn replay_game(game: &Game, acceptable_words: &BTreeSet<String>, first_ranking: BinaryHeap<WordEntrophy>, language: &Language, theme: Theme, is_tty: bool) {
+    let answer = game.answer.to_lowercase();
+    let mut candidates = acceptable_words.clone();
+    let mut ranking = first_ranking;
+    let mut alphabet: HashMap<char, char> = HashMap::new();
+    for c in language.letters() {
+        alphabet.insert(c, 'X');
+    }
+    let mut scores: Vec<(String, i32, i32)> = Vec::new();
+    for (index, guess) in game.guesses.iter().enumerate() {
+        let guess = guess.to_lowercase();
+        let state = compute_state(&guess, &answer);
+        //按游戏的变体显示当时看到的颜色
+        let shown = game.variant.feedback(&guess, &answer, state);
+        print!("Guess {}: ", index + 1);
+        print_row(&mut io::stdout(), &guess, &shown, game.variant, theme).unwrap();
+        println!();
+        if game.variant.shows_keyboard() {
+            for (letter, x) in guess.chars().zip(shown) {
+                if let Some(key) = alphabet.get_mut(&letter) {
+                    match x {
+                        2 => *key = 'G',
+                        1 if *key != 'G' => *key = 'Y',
+                        0 if *key == 'X' => *key = 'R',
+                        _ => {},
+                    }
+                }
+            }
+            print_keyboard(&mut io::stdout(), &alphabet, &language.keyboard, theme).unwrap();
+            println!();
+        }
+
+        let remaining = filter_words(&candidates, &guess, &state);
+        println!("Candidates: {} -> {}", candidates.len(), remaining.len());
+        let guess_bits = bits_of(&guess, &ranking, &candidates);
+        let skill;
+        if let Some(best) = ranking.peek() {
+            let best_bits = best.1 as f64 / 1000000000.0;
+            println!("Solver would play: {} {:.4}, yours: {} {:.4}",
+                theme.good().apply_to(language::to_upper(&best.0)), best_bits,
+                theme.style('Y').bold().apply_to(language::to_upper(&guess)), guess_bits);
+            skill = skill_score(guess_bits, best_bits, candidates.contains(&guess));
+        }
+        else {
+            skill = 100;
+        }
+        let luck = luck_score(&guess, &answer, &candidates);
+        println!("Skill: {} Luck: {}", skill, luck);
+        scores.push((language::to_upper(&guess), skill, luck));
+
+        candidates = remaining;
+        if index + 1 < game.guesses.len() {
+            ranking = information_entrophy(&candidates);
+            if is_tty {
+                print!("Press Enter for the next guess ");
+                io::stdout().flush().unwrap();
+                let mut line = String::new();
+                io::stdin().read_line(&mut line).unwrap();
+            }
+        }
+    }
+    println!("Answer: {}", language::to_upper(&game.answer));
+    println!("Guess Skill Luck");
+    for (guess, skill, luck) in &scores {
+        println!("{} {:>5} {:>4}", guess, skill, luck);
+    }
+    if !scores.is_empty() {
+        let skill: i32 = scores.iter().map(|x| x.1).sum();
+        let luck: i32 = scores.iter().map(|x| x.2).sum();
+        println!("Average skill: {:.2} Average luck: {:.2}",
+            skill as f64 / scores.len() as f64,
+            luck as f64 / scores.len() as f64);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{compute_state, Game};
+use crate::validate::MAX_GUESSES;
+
+///States of every guess in a stored game, as shown in its variant
+pub fn game_states(game: &Game) -> Vec<[i32; 5]> {
+    let answer = game.answer.to_lowercase();
+    game.guesses
+        .iter()
+        .map(|guess| guess.to_lowercase())
+        .map(|guess| game.variant.feedback(&guess, &answer, compute_state(&guess, &answer)))
+        .collect()
+}
+
+///Render the states of one game as the familiar shareable summary
+///e.g. "Wordle 123 4/6*" followed by one row per guess
+pub fn share_text(number: Option<usize>, states: &[[i32; 5]], difficult: bool, ascii: bool) -> String {
+    let mut result = String::from("Wordle");
+    if let Some(x) = number {
+        result += &format!(" {}", x);
+    }
+    match states.last() {
+        Some([2, 2, 2, 2, 2]) => result += &format!(" {}/{}", states.len(), MAX_GUESSES),
+        _ => result += &format!(" X/{}", MAX_GUESSES),
+    }
+    if difficult {
+        result.push('*');
+    }
+    for state in states {
+        result.push('\n');
+        result += &row_text(state, ascii);
+    }
+    result
+}
+
+///Render the states of one guess as a row of tiles
+pub fn row_text(state: &[i32; 5], ascii: bool) -> String {
+    let mut result = String::new();
+    for x in state {
+        let tile = match (x, ascii) {
+            (2, false) => "🟩",
+            (1, false) => "🟨",
+            (_, false) => "⬛",
+            (2, true) => "G",
+            (1, true) => "Y",
+            (_, true) => "R",
+        };
+        result += tile;
+    }
+    result
+}
@@ -0,0 +1,262 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use crate::feedback::compute_state;
+
+///Tuple for one word(string) and its information entrophy
+#[derive(Debug, Eq)]
+pub struct WordEntrophy(pub String, pub i64);
+
+impl PartialEq for WordEntrophy {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialOrd for WordEntrophy {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for WordEntrophy {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.1.cmp(&other.1) {
+            Ordering::Greater => Ordering::Greater,
+            Ordering::Less => Ordering::Less,
+            Ordering::Equal => self.0.cmp(&other.0).reverse()
+        }
+    }
+}
+
+///Check whether word could be the answer given the state of guess
+///2->Green 1->Yellow 0->Red
+pub fn word_matches(word: &str, guess: &str, state: &[i32; 5]) -> bool {
+    //新建word字符集映射，反映各字母出现次数
+    let mut map: HashMap<char, i32> = HashMap::new();
+    for c in word.chars() {
+        *map.entry(c).or_insert(0) += 1;
+    }
+    //Green
+    for (i, x) in state.iter().enumerate() {
+        if *x == 2 {
+            if word.chars().nth(i) != guess.chars().nth(i) {
+                return false;
+            }
+            if let Some(x) = word.chars().nth(i) {
+                if let Some(z) = map.get_mut(&x) {
+                    *z -= 1;
+                }
+            }
+        }
+    }
+    //Yellow
+    for (i, x) in state.iter().enumerate() {
+        if *x == 1 {
+            if word.chars().nth(i) == guess.chars().nth(i) {
+                return false;
+            }
+            if let Some(x) = guess.chars().nth(i) {
+                match map.get_mut(&x) {
+                    Some(z) if *z != 0 => { *z -= 1; },
+                    _ => { return false; }
+                }
+            }
+        }
+    }
+    //Red
+    for (i, x) in state.iter().enumerate() {
+        if *x == 0 {
+            if let Some(x) = guess.chars().nth(i) {
+                if let Some(z) = map.get(&x) {
+                    if *z != 0 {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+    true
+}
+
+///Keep the words which could still be the answer given the state of guess
+pub fn filter_words(words: &BTreeSet<String>, guess: &str, state: &[i32; 5]) -> BTreeSet<String> {
+    words
+        .iter()
+        .filter(|word| word_matches(word, guess, state))
+        .cloned()
+        .collect()
+}
+
+///Index of a state among the 3^5 patterns a guess can give
+pub fn state_index(state: &[i32; 5]) -> usize {
+    state.iter().fold(0, |x, y| x * 3 + *y as usize)
+}
+
+///Answers grouped by the pattern a guess gives for them, counted by state_index
+pub fn pattern_counts(guess: &str, answers: &BTreeSet<String>) -> [usize; 243] {
+    let mut counts = [0; 243];
+    for answer in answers {
+        counts[state_index(&compute_state(guess, answer))] += 1;
+    }
+    counts
+}
+
+///Shannon entrophy in bits of the answers split into buckets, each bucket given by its count or weight
+pub fn bucket_entrophy(buckets: &[f64], total: f64) -> f64 {
+    let mut sum: f64 = 0.0;
+    for weight in buckets.iter().filter(|x| **x > 0.0) {
+        let p = weight / total;
+        sum -= p * p.log2();
+    }
+    sum
+}
+
+///Compute the information entrophy of one guess over the library
+pub fn guess_entrophy(word_target: &str, library: &BTreeSet<String>) -> f64 {
+    //2->Green 1->Yellow 0->Red
+    let counts = pattern_counts(word_target, library);
+    bucket_entrophy(&counts.map(|x| x as f64), library.len() as f64)
+}
+
+///Compute each words' information entrophy in the library
+pub fn information_entrophy(library: &BTreeSet<String>) -> BinaryHeap<WordEntrophy> {
+    let mut result: BinaryHeap<WordEntrophy> = BinaryHeap::new();
+    for word_target in library {
+        let sum = guess_entrophy(word_target, library);
+        result.push(WordEntrophy(word_target.clone(), (sum * 1000000000.0) as i64));
+    }
+    result
+}
+
+///Weight of each word being the answer, words missing weigh 0
+pub type Weights = HashMap<String, f64>;
+
+///Final words at full weight and acceptable-only words at other_weight
+///A frequency table scales each weight by the word's frequency relative to the most frequent one,
+///words missing from the table take the least frequency in it
+pub fn answer_weights(final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>, other_weight: f64,
+                      frequency: Option<&HashMap<String, f64>>) -> Weights {
+    let most = frequency.and_then(|x| x.values().copied().reduce(f64::max)).unwrap_or(1.0);
+    let least = frequency.and_then(|x| x.values().copied().reduce(f64::min)).unwrap_or(1.0);
+    let mut weights = Weights::new();
+    for word in acceptable_words.union(final_words) {
+        let base = if final_words.contains(word) { 1.0 } else { other_weight };
+        let scale = match frequency {
+            Some(x) if most > 0.0 => x.get(word).copied().unwrap_or(least) / most,
+            _ => 1.0,
+        };
+        if base * scale > 0.0 {
+            weights.insert(word.clone(), base * scale);
+        }
+    }
+    weights
+}
+
+///Words of the library that may be the answer with their weights
+fn weighted_answers<'a>(library: &'a BTreeSet<String>, weights: &Weights) -> Vec<(&'a str, f64)> {
+    library.iter().filter_map(|x| weights.get(x).map(|w| (x.as_str(), *w))).collect()
+}
+
+///Entrophy of one guess over weighted answers and the chance of it winning right away
+fn weighted_entrophy_over(guess: &str, answers: &[(&str, f64)], total: f64) -> (f64, f64) {
+    if total <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let mut buckets = [0.0; 243];
+    let mut win = 0.0;
+    for (word, weight) in answers {
+        buckets[state_index(&compute_state(guess, word))] += weight;
+        if *word == guess {
+            win = *weight;
+        }
+    }
+    (bucket_entrophy(&buckets, total), win / total)
+}
+
+///Information entrophy of one guess when the answers in the library are as likely as their weights
+pub fn weighted_guess_entrophy(guess: &str, library: &BTreeSet<String>, weights: &Weights) -> f64 {
+    let answers = weighted_answers(library, weights);
+    let total = answers.iter().map(|x| x.1).sum();
+    weighted_entrophy_over(guess, &answers, total).0
+}
+
+///Score of one guess for the solver, its weighted entrophy plus its chance of winning right away
+///It is not an entrophy, the chance is added so that a guess which may be the answer comes first
+pub fn weighted_guess_score(guess: &str, library: &BTreeSet<String>, weights: &Weights) -> f64 {
+    let answers = weighted_answers(library, weights);
+    let total = answers.iter().map(|x| x.1).sum();
+    let (entrophy, win) = weighted_entrophy_over(guess, &answers, total);
+    entrophy + win
+}
+
+///Rank each word in the library by its weighted_guess_score
+pub fn weighted_guess_scores(library: &BTreeSet<String>, weights: &Weights) -> BinaryHeap<WordEntrophy> {
+    let answers = weighted_answers(library, weights);
+    let total = answers.iter().map(|x| x.1).sum();
+    let mut result: BinaryHeap<WordEntrophy> = BinaryHeap::new();
+    for word_target in library {
+        let (entrophy, win) = weighted_entrophy_over(word_target, &answers, total);
+        result.push(WordEntrophy(word_target.clone(), ((entrophy + win) * 1000000000.0) as i64));
+    }
+    result
+}
+
+///Guesses of one game choosing the guess of the most entrophy each time, starting with opener
+///The last guess is the answer unless the game is lost after 6
+pub fn solver_guesses(answer: &str, acceptable_words: &BTreeSet<String>, opener: &str) -> Vec<String> {
+    let mut candidates = acceptable_words.clone();
+    let mut guesses = vec![opener.to_string()];
+    while guesses.len() < 6 {
+        let guess = &guesses[guesses.len() - 1];
+        if guess == answer {
+            break;
+        }
+        //更新信息熵集
+        candidates = filter_words(&candidates, guess, &compute_state(guess, answer));
+        match information_entrophy(&candidates).pop() {
+            Some(x) => guesses.push(x.0),
+            None => break,
+        }
+    }
+    guesses
+}
+
+///Guesses the solver takes for each answer starting with opener, the same as play_out for each of them
+///Answers giving the same feedback share the solver's next guess, so every guess is worked out once
+pub fn play_out_all(answers: &BTreeSet<String>, acceptable_words: &BTreeSet<String>, opener: &str) -> HashMap<String, Option<usize>> {
+    let mut result = HashMap::new();
+    let answers: Vec<&str> = answers.iter().map(|x| x.as_str()).collect();
+    play_out_subtree(acceptable_words, opener, &answers, 1, &mut result);
+    result
+}
+
+///Answers reaching guess after the guesses played, split by the feedback they give
+fn play_out_subtree(candidates: &BTreeSet<String>, guess: &str, answers: &[&str], played: usize,
+                    result: &mut HashMap<String, Option<usize>>) {
+    let mut branches: HashMap<[i32; 5], Vec<&str>> = HashMap::new();
+    for answer in answers {
+        if *answer == guess {
+            result.insert(answer.to_string(), Some(played));
+        }
+        else if played >= 6 {
+            result.insert(answer.to_string(), None);
+        }
+        else {
+            branches.entry(compute_state(guess, answer)).or_default().push(answer);
+        }
+    }
+    for (state, answers) in branches {
+        let candidates = filter_words(candidates, guess, &state);
+        match information_entrophy(&candidates).pop() {
+            Some(x) => play_out_subtree(&candidates, &x.0, &answers, played + 1, result),
+            None => result.extend(answers.iter().map(|x| (x.to_string(), None))),
+        }
+    }
+}
+
+///Play one game with the solver starting with opener
+///Return the number of guesses, None if the answer is not found in 6
+pub fn play_out(answer: &str, acceptable_words: &BTreeSet<String>, opener: &str) -> Option<usize> {
+    let guesses = solver_guesses(answer, acceptable_words, opener);
+    if guesses.last().is_some_and(|x| x == answer) { Some(guesses.len()) } else { None }
+}
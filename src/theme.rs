@@ -0,0 +1,86 @@
+use console::Style;
+use serde::{Deserialize, Serialize};
+use crate::language;
+
+///Colors of the letter states
+#[derive(clap::ValueEnum, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    ///Green, yellow and grey like the original game
+    Default,
+    ///Bold letters on colored backgrounds
+    HighContrast,
+    ///Orange for correct and blue for present letters
+    Colorblind,
+    ///No colors, letters are marked as [A] correct, (A) present, -A- absent and  A  unknown
+    Monochrome,
+}
+
+impl Theme {
+    ///The theme assigned, or monochrome if NO_COLOR is set
+    pub fn resolve(theme: Option<Theme>) -> Theme {
+        match theme {
+            Some(x) => x,
+            None if std::env::var_os("NO_COLOR").is_some_and(|x| !x.is_empty()) => Theme::Monochrome,
+            None => Theme::Default,
+        }
+    }
+
+    ///Style of a letter state: G correct, Y present, R absent and X unknown
+    pub fn style(self, state: char) -> Style {
+        let style = Style::new();
+        match (self, state) {
+            (Theme::Monochrome, _) => style,
+            (_, 'X') => style.dim(),
+            (Theme::Default, 'G') => style.green(),
+            (Theme::Default, 'Y') => style.yellow(),
+            (Theme::Default, _) => style.color256(244),
+            (Theme::HighContrast, 'G') => style.black().on_green().bold(),
+            (Theme::HighContrast, 'Y') => style.black().on_yellow().bold(),
+            (Theme::HighContrast, _) => style.white().on_black().bold(),
+            (Theme::Colorblind, 'G') => style.color256(208),
+            (Theme::Colorblind, 'Y') => style.color256(39),
+            (Theme::Colorblind, _) => style.color256(244),
+        }
+    }
+
+    ///One letter in the color of its state
+    pub fn letter(self, letter: char, state: char) -> String {
+        let upper = language::upper_char(letter);
+        let text = match (self, state) {
+            (Theme::Monochrome, 'G') => format!("[{}]", upper),
+            (Theme::Monochrome, 'Y') => format!("({})", upper),
+            (Theme::Monochrome, 'R') => format!("-{}-", upper),
+            (Theme::Monochrome, _) => format!(" {} ", upper),
+            _ => upper.to_string(),
+        };
+        self.style(state).apply_to(text).to_string()
+    }
+
+    ///Width of one letter on screen
+    pub fn width(self) -> usize {
+        if self == Theme::Monochrome { 3 } else { 1 }
+    }
+
+    ///Style of good news in messages and stats
+    pub fn good(self) -> Style {
+        self.style('G').bold()
+    }
+
+    ///Style of bad news in messages and stats
+    pub fn bad(self) -> Style {
+        match self {
+            Theme::Monochrome => Style::new(),
+            Theme::Colorblind => Style::new().color256(39).bold(),
+            _ => Style::new().red().bold(),
+        }
+    }
+
+    ///Style of neutral values in messages and stats
+    pub fn info(self) -> Style {
+        match self {
+            Theme::Monochrome => Style::new().bold(),
+            _ => Style::new().cyan().bold(),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+///Source of the current time, a fake one makes timed games testable
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+///The real clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+///A clock that only moves when told to
+pub struct ManualClock {
+    now: Cell<Instant>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock { now: Cell::new(Instant::now()) }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+///Time limits and per-guess timing of one game
+pub struct Timer<'a> {
+    clock: &'a dyn Clock,
+    game_deadline: Option<Instant>,
+    guess_limit: Option<Duration>,
+    guess_start: Instant,
+    ///Whether guess times are recorded in the game
+    record: bool,
+}
+
+impl<'a> Timer<'a> {
+    ///Start the clock, limits are in seconds
+    pub fn new(clock: &'a dyn Clock, game_limit: Option<u64>, guess_limit: Option<u64>, record: bool) -> Self {
+        let now = clock.now();
+        Timer {
+            clock,
+            game_deadline: game_limit.map(|x| now + Duration::from_secs(x)),
+            guess_limit: guess_limit.map(Duration::from_secs),
+            guess_start: now,
+            record,
+        }
+    }
+
+    ///When the current guess must be in, the earlier of both limits
+    pub fn deadline(&self) -> Option<Instant> {
+        let guess_deadline = self.guess_limit.map(|x| self.guess_start + x);
+        match (self.game_deadline, guess_deadline) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        }
+    }
+
+    ///Whole seconds left for the current guess, rounded up
+    pub fn seconds_left(&self) -> Option<u64> {
+        self.deadline().map(|x| {
+            let left = x.saturating_duration_since(self.clock.now());
+            left.as_secs() + if left.subsec_nanos() > 0 { 1 } else { 0 }
+        })
+    }
+
+    ///Finish the current guess and start the next one
+    ///Return the milliseconds taken if times are recorded
+    pub fn lap(&mut self) -> Option<u64> {
+        let now = self.clock.now();
+        let time = now.duration_since(self.guess_start).as_millis() as u64;
+        self.guess_start = now;
+        if self.record { Some(time) } else { None }
+    }
+}
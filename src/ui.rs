@@ -0,0 +1,62 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use wordle::game::Mode;
+
+///How the game talks to the player
+#[derive(clap::ValueEnum, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Ui {
+    ///Interactive without colors, letters marked like the monochrome theme
+    Plain,
+    ///Interactive with colors
+    Color,
+    ///One line per guess in the format of the course tests
+    Test,
+    ///One json object per line for each event
+    Jsonl,
+    ///Interactive, redrawing the whole board after each guess
+    Tui,
+}
+
+impl fmt::Display for Ui {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ui::Plain => write!(f, "plain"),
+            Ui::Color => write!(f, "color"),
+            Ui::Test => write!(f, "test"),
+            Ui::Jsonl => write!(f, "jsonl"),
+            Ui::Tui => write!(f, "tui"),
+        }
+    }
+}
+
+impl Ui {
+    ///The ui assigned, or color on a terminal and test otherwise
+    pub fn resolve(ui: Option<Ui>) -> Ui {
+        match ui {
+            Some(x) => x,
+            None if atty::is(atty::Stream::Stdout) => Ui::Color,
+            None => Ui::Test,
+        }
+    }
+
+    ///Whether a person is expected on the other side, asking names and showing prompts
+    pub fn interactive(self) -> bool {
+        matches!(self, Ui::Plain | Ui::Color | Ui::Tui)
+    }
+
+    ///Mode of the game loop
+    pub fn mode(self) -> Mode {
+        match self {
+            Ui::Plain | Ui::Color => Mode::Normal,
+            Ui::Tui => Mode::Tui,
+            Ui::Test => Mode::Test,
+            Ui::Jsonl => Mode::Jsonl,
+        }
+    }
+
+    ///Whether colors are written, regardless of the terminal
+    pub fn colors(self) -> bool {
+        matches!(self, Ui::Color | Ui::Tui)
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use crate::{Game, User};
+
+///Maximum guesses allowed in one game round
+pub const MAX_GUESSES: usize = 6;
+
+///Reasons for one stored game to break the game rules
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameError {
+    NoGuesses,
+    AnswerLength(String),
+    AnswerNotFinal(String),
+    GuessLength(usize, String),
+    GuessNotAcceptable(usize, String),
+    TooManyGuesses(usize),
+    GuessAfterWin(usize),
+    ///Number of guess times and number of guesses
+    TimesMismatch(usize, usize),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::NoGuesses => write!(f, "no guesses recorded"),
+            GameError::AnswerLength(answer) => write!(f, "answer \"{}\" is not 5 letters long", answer),
+            GameError::AnswerNotFinal(answer) => write!(f, "answer \"{}\" is not in the final word list", answer),
+            GameError::GuessLength(index, guess) => write!(f, "guess {} \"{}\" is not 5 letters long", index + 1, guess),
+            GameError::GuessNotAcceptable(index, guess) => write!(f, "guess {} \"{}\" is not in the acceptable word list", index + 1, guess),
+            GameError::TooManyGuesses(count) => write!(f, "{} guesses recorded, at most {} allowed", count, MAX_GUESSES),
+            GameError::GuessAfterWin(index) => write!(f, "guess {} hits the answer but the game goes on", index + 1),
+            GameError::TimesMismatch(times, guesses) => write!(f, "{} guess time(s) recorded for {} guess(es)", times, guesses),
+        }
+    }
+}
+
+///Final and acceptable word lists the stored words are checked against
+pub type WordLists<'a> = (&'a BTreeSet<String>, &'a BTreeSet<String>);
+
+///Check one game against the game rules, and against the word lists if given
+///The lists a game was played with are not stored, so they are only given when asked for
+pub fn validate_game(game: &Game, lists: Option<WordLists>) -> Vec<GameError> {
+    let mut errors: Vec<GameError> = Vec::new();
+    let answer = game.answer.to_lowercase();
+    if answer.chars().count() != 5 {
+        errors.push(GameError::AnswerLength(game.answer.clone()));
+    }
+    else if lists.is_some_and(|(final_words, _)| !final_words.contains(&answer)) {
+        errors.push(GameError::AnswerNotFinal(game.answer.clone()));
+    }
+    //超时的游戏可以没有猜测
+    if game.guesses.is_empty() && !game.timed_out {
+        errors.push(GameError::NoGuesses);
+    }
+    if !game.times.is_empty() && game.times.len() != game.guesses.len() {
+        errors.push(GameError::TimesMismatch(game.times.len(), game.guesses.len()));
+    }
+    if game.guesses.len() > MAX_GUESSES {
+        errors.push(GameError::TooManyGuesses(game.guesses.len()));
+    }
+    for (index, guess) in game.guesses.iter().enumerate() {
+        let word = guess.to_lowercase();
+        if word.chars().count() != 5 {
+            errors.push(GameError::GuessLength(index, guess.clone()));
+        }
+        else if lists.is_some_and(|(_, acceptable_words)| !acceptable_words.contains(&word)) {
+            errors.push(GameError::GuessNotAcceptable(index, guess.clone()));
+        }
+        //猜中答案后不应再有猜测
+        if word == answer && index + 1 != game.guesses.len() {
+            errors.push(GameError::GuessAfterWin(index));
+        }
+    }
+    errors
+}
+
+///Check every stored game, returning the index of each bad game with its errors
+pub fn validate_user(u: &User, lists: Option<WordLists>) -> Vec<(usize, Vec<GameError>)> {
+    let mut result: Vec<(usize, Vec<GameError>)> = Vec::new();
+    if let Some(games) = &u.games {
+        for (index, game) in games.iter().enumerate() {
+            let errors = validate_game(game, lists);
+            if !errors.is_empty() {
+                result.push((index, errors));
+            }
+        }
+    }
+    result
+}
+
+///Drop every bad game from the user, returning what was dropped
+pub fn repair_user(u: &mut User, lists: Option<WordLists>) -> Vec<(usize, Vec<GameError>)> {
+    let dropped = validate_user(u, lists);
+    if dropped.is_empty() {
+        return dropped;
+    }
+    //练习不计入总局数
+    let mut rounds = 0;
+    if let Some(games) = &mut u.games {
+        rounds = dropped.iter().filter(|x| !games[x.0].practice).count();
+        let mut index = 0;
+        games.retain(|_| {
+            let keep = !dropped.iter().any(|x| x.0 == index);
+            index += 1;
+            keep
+        });
+    }
+    if let Some(ref mut x) = u.total_rounds {
+        *x = (*x - rounds as i32).max(0);
+    }
+    dropped
+}
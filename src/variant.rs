@@ -0,0 +1,90 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+///Rule variants changing what the feedback of a guess reveals
+///The answer is scored as usual, the variant only changes the colors shown
+#[derive(clap::ValueEnum, Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Variant {
+    ///The usual rules
+    #[default]
+    Classic,
+    ///Only green letters are revealed, present letters are shown as absent
+    NoYellows,
+    ///One tile of each row shows a wrong color, like Fibble, a wrong guess never shows all green
+    Lying,
+    ///The colors of the guesses are shown but the keyboard is not
+    HiddenKeyboard,
+    ///Only the numbers of green and yellow tiles are revealed, like Mastermind
+    Counts,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Variant::Classic => write!(f, "classic"),
+            Variant::NoYellows => write!(f, "no-yellows"),
+            Variant::Lying => write!(f, "lying"),
+            Variant::HiddenKeyboard => write!(f, "hidden-keyboard"),
+            Variant::Counts => write!(f, "counts"),
+        }
+    }
+}
+
+impl Variant {
+    pub fn is_classic(&self) -> bool {
+        *self == Variant::Classic
+    }
+
+    ///Whether the feedback shown is the true one, so that hints, hard mode and explanations make sense
+    pub fn truthful(self) -> bool {
+        matches!(self, Variant::Classic | Variant::HiddenKeyboard)
+    }
+
+    ///Whether the keyboard is shown and updated
+    pub fn shows_keyboard(self) -> bool {
+        matches!(self, Variant::Classic | Variant::NoYellows | Variant::Lying)
+    }
+
+    ///Feedback shown for a guess given its true state, a correct guess is always shown as is
+    ///The counts variant sorts the tiles, greens first, so positions tell nothing
+    ///2->Green 1->Yellow 0->Red
+    pub fn feedback(self, guess: &str, answer: &str, state: [i32; 5]) -> [i32; 5] {
+        if guess == answer {
+            return state;
+        }
+        let mut result = state;
+        match self {
+            Variant::Classic | Variant::HiddenKeyboard => {},
+            Variant::NoYellows => {
+                for x in result.iter_mut().filter(|x| **x == 1) {
+                    *x = 0;
+                }
+            },
+            Variant::Lying => {
+                //谎言只由猜测和答案决定，重放和分享时保持一致
+                let seed = lie_seed(guess, answer);
+                let position = (seed % 5) as usize;
+                let truth = result[position];
+                result[position] = (truth + 1 + (seed / 5 % 2) as i32) % 3;
+                //错误的猜测不能显示为全绿，改用另一种颜色
+                if result == [2; 5] {
+                    result[position] = (truth + 2 - (seed / 5 % 2) as i32) % 3;
+                }
+            },
+            Variant::Counts => result.sort_by(|a, b| b.cmp(a)),
+        }
+        result
+    }
+}
+
+///Hash of a guess and its answer choosing the lying tile and its color
+fn lie_seed(guess: &str, answer: &str) -> u64 {
+    //FNV-1a
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in guess.bytes().chain([b'/']).chain(answer.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
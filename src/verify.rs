@@ -0,0 +1,60 @@
+use std::collections::BTreeSet;
+use crate::compute_state;
+
+///Parse one row of a shared grid, emoji or letters
+pub fn parse_row(line: &str) -> Option<[i32; 5]> {
+    let mut state = [0; 5];
+    let mut i = 0;
+    for c in line.chars() {
+        let x = match c {
+            '🟩' | 'G' => 2,
+            '🟨' | 'Y' => 1,
+            '⬛' | '⬜' | 'R' => 0,
+            _ if c.is_whitespace() => continue,
+            _ => return None,
+        };
+        if i == 5 {
+            return None;
+        }
+        state[i] = x;
+        i += 1;
+    }
+    if i == 5 { Some(state) } else { None }
+}
+
+///Parse a pasted grid, returning the day number in its header and the rows
+pub fn parse_grid(text: &str) -> Result<(Option<usize>, Vec<[i32; 5]>), String> {
+    let mut day: Option<usize> = None;
+    let mut rows: Vec<[i32; 5]> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        //标题行 e.g. "Wordle 123 4/6*"
+        if let Some(header) = line.strip_prefix("Wordle") {
+            let words: Vec<&str> = header.split_whitespace().collect();
+            if words.len() == 2 {
+                day = words[0].parse().ok();
+            }
+            continue;
+        }
+        match parse_row(line) {
+            Some(state) => rows.push(state),
+            None => return Err(format!("line {}: \"{}\" is not a row of 5 tiles", index + 1, line)),
+        }
+    }
+    if rows.is_empty() {
+        return Err("no rows found in the grid".to_string());
+    }
+    Ok((day, rows))
+}
+
+///Words in the library that would produce the state against the answer
+pub fn possible_guesses(library: &BTreeSet<String>, answer: &str, state: &[i32; 5]) -> Vec<String> {
+    library
+        .iter()
+        .filter(|word| compute_state(word, answer) == *state)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,94 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::language;
+
+///Words parsed from a list file together with the problems found
+pub struct ParsedList {
+    pub words: BTreeSet<String>,
+    ///Line number and content of each line that is not a word
+    pub invalid: Vec<(usize, String)>,
+    ///Line number of each repeated word and the line it first appeared
+    pub duplicates: Vec<(usize, String, usize)>,
+    pub blank: Vec<usize>,
+}
+
+///Check whether one normalized line is a valid word
+pub fn is_valid_word(word: &str) -> bool {
+    language::is_five_letters(word) && word.chars().all(|c| c.is_alphabetic())
+}
+
+///Parse the text of a list file, one word each line
+pub fn parse_list(text: &str) -> ParsedList {
+    let mut result = ParsedList {
+        words: BTreeSet::new(),
+        invalid: Vec::new(),
+        duplicates: Vec::new(),
+        blank: Vec::new(),
+    };
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let word = language::normalize(line);
+        if word.is_empty() {
+            result.blank.push(line_number);
+        }
+        else if !is_valid_word(&word) {
+            result.invalid.push((line_number, line.to_string()));
+        }
+        else if let Some(first) = first_seen.get(&word) {
+            result.duplicates.push((line_number, word, *first));
+        }
+        else {
+            first_seen.insert(word.clone(), line_number);
+            result.words.insert(word);
+        }
+    }
+    result
+}
+
+///Read and parse a list file
+pub fn read_list(path: &str) -> Result<ParsedList, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => Ok(parse_list(&text)),
+        Err(e) => Err(format!("{}: {}", path, e)),
+    }
+}
+
+///Read a frequency file, one word and its count or frequency each line, e.g. `crane 1200` or `crane,1200`
+pub fn read_frequency(path: &str) -> Result<HashMap<String, f64>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut result = HashMap::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = line.split_once(|c: char| c == ',' || c.is_whitespace())
+            .map(|(word, count)| (language::normalize(word), count.trim().parse::<f64>()));
+        match parsed {
+            Some((word, Ok(count))) if is_valid_word(&word) && count >= 0.0 => {
+                result.insert(word, count);
+            },
+            _ => return Err(format!("{}:{}: invalid frequency line \"{}\"", path, index + 1, line)),
+        }
+    }
+    Ok(result)
+}
+
+///Final words missing from the acceptable words
+pub fn missing_words(final_words: &BTreeSet<String>, acceptable_words: &BTreeSet<String>) -> Vec<String> {
+    final_words.difference(acceptable_words).cloned().collect()
+}
+
+///Count of each letter in total and at each position, the most used letter first
+pub fn letter_frequency(words: &BTreeSet<String>) -> Vec<(char, usize, [usize; 5])> {
+    let mut table: BTreeMap<char, (usize, [usize; 5])> = BTreeMap::new();
+    for word in words {
+        for (i, c) in word.chars().enumerate().take(5) {
+            let entry = table.entry(c).or_insert((0, [0; 5]));
+            entry.0 += 1;
+            entry.1[i] += 1;
+        }
+    }
+    let mut result: Vec<(char, usize, [usize; 5])> = table.into_iter().map(|(c, x)| (c, x.0, x.1)).collect();
+    result.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    result
+}
@@ -9,7 +9,6 @@ use std::sync::Once;
 use assert_json_diff::assert_json_eq;
 use lazy_static::lazy_static;
 use pretty_assertions::assert_eq;
-use serde_json;
 
 // The code was originally written by Jack O'Connor (@oconnor663)
 // Taken from https://github.com/oconnor663/os_pipe.rs/blob/f41c58e503e1efc5e4d0edfcd2e756b3a81b4232/src/lib.rs#L281-L314
@@ -41,7 +40,7 @@ fn build_and_find_path(name: &str) -> PathBuf {
     static CARGO_BUILD_ONCE: Once = Once::new();
     CARGO_BUILD_ONCE.call_once(|| {
         let mut build_command = Command::new("cargo");
-        build_command.args(&["build", "--quiet"]);
+        build_command.args(["build", "--quiet"]);
         if !cfg!(debug_assertions) {
             build_command.arg("--release");
         }
@@ -71,6 +70,8 @@ pub struct TestCase {
     arguments: Vec<String>,
     input: String,
     answer: String,
+    // environment variables from the optional `.env` file, one KEY=VALUE per line
+    env: Vec<(String, String)>,
 }
 
 impl TestCase {
@@ -83,6 +84,7 @@ impl TestCase {
         let in_content = std::fs::read_to_string(in_file).unwrap();
         let ans_content = std::fs::read_to_string(ans_file).unwrap();
         let args_content = std::fs::read_to_string(args_file).unwrap();
+        let env_content = std::fs::read_to_string(case_dir.join(format!("{}.env", name))).unwrap_or_default();
 
         Self {
             name: name.to_string(),
@@ -94,12 +96,85 @@ impl TestCase {
                 .collect(),
             input: in_content,
             answer: ans_content,
+            env: env_content
+                .lines()
+                .filter_map(|s| s.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Names of every case in `tests/cases`, found by their `.args` files
+    #[allow(dead_code)] // only used by the golden runner
+    pub fn discover() -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(Path::new("tests").join("cases"))
+            .unwrap()
+            .filter_map(|entry| {
+                let name = entry.unwrap().file_name().to_string_lossy().to_string();
+                name.strip_suffix(".args").map(|s| s.to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[allow(dead_code)] // only used by the golden runner
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn state_files(&self) -> (PathBuf, PathBuf, PathBuf) {
+        let case_dir = Path::new("tests").join("cases");
+        (
+            case_dir.join(format!("{}.before.json", self.name)),
+            case_dir.join(format!("{}.run.json", self.name)),
+            case_dir.join(format!("{}.after.json", self.name)),
+        )
+    }
+
+    /// Run the case the way its files ask for:
+    /// an empty `.ans` expects an error exit, a `.before.json` also compares the state file
+    #[allow(dead_code)] // only used by the golden runner
+    pub fn run_golden(&mut self) {
+        if self.answer.trim().is_empty() {
+            self.run_and_expect_exit();
+        } else if self.state_files().0.exists() {
+            self.run_and_compare_game_state();
+        } else {
+            self.run_and_compare_result();
+        }
+    }
+
+    /// Rewrite `.ans` and `.after.json` from the current program instead of comparing
+    /// Cases expecting an error exit are only checked
+    #[allow(dead_code)] // only used by the golden runner
+    pub fn update_golden(&mut self) {
+        if self.answer.trim().is_empty() {
+            self.run_and_expect_exit();
+            return;
+        }
+        let (before_state_file, run_state_file, after_state_file) = self.state_files();
+        let with_state = before_state_file.exists();
+        if with_state {
+            std::fs::copy(&before_state_file, &run_state_file).unwrap();
+            self.arguments.append(&mut vec![
+                "--state".to_string(),
+                run_state_file.to_str().unwrap().to_string(),
+            ]);
+        }
+        let (success, output) = self.output();
+        assert!(success, "case {} should exit normally", self.name);
+        let ans_file = Path::new("tests").join("cases").join(format!("{}.ans", self.name));
+        std::fs::write(ans_file, output.trim_end().to_string() + "\n").unwrap();
+        if with_state {
+            std::fs::copy(&run_state_file, &after_state_file).unwrap();
         }
     }
 
     fn execute_program_and_feed_input(&self) -> Child {
         let mut command = Command::new(EXE_PATH.as_os_str())
             .args(&self.arguments)
+            .envs(self.env.iter().map(|(k, v)| (k, v)))
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .spawn()
@@ -115,7 +190,7 @@ impl TestCase {
         command
     }
 
-    pub fn run_and_compare_result(&self) {
+    fn output(&self) -> (bool, String) {
         let mut command = self.execute_program_and_feed_input();
         // read stdout from user program
         let mut output = Vec::new();
@@ -129,12 +204,14 @@ impl TestCase {
 
         // command.try_wait();
 
+        (command.wait().expect("failed to wait on process").success(), output)
+    }
+
+    pub fn run_and_compare_result(&self) {
+        let (success, output) = self.output();
+
         // wait for the program to exit normally
-        assert!(
-            command.wait().expect("failed to wait on process").success(),
-            "case {} should exit normally",
-            self.name
-        );
+        assert!(success, "case {} should exit normally", self.name);
 
         // compare result
         assert_eq!(
@@ -147,10 +224,7 @@ impl TestCase {
 
     pub fn run_and_compare_game_state(&mut self) {
         // read state before & end
-        let case_dir = Path::new("tests").join("cases");
-        let before_state_file = case_dir.join(format!("{}.before.json", self.name));
-        let run_state_file = case_dir.join(format!("{}.run.json", self.name));
-        let after_state_file = case_dir.join(format!("{}.after.json", self.name));
+        let (before_state_file, run_state_file, after_state_file) = self.state_files();
 
         // run with temporary state file
         std::fs::copy(&before_state_file, &run_state_file).unwrap();
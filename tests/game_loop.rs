@@ -0,0 +1,168 @@
+use std::collections::{BTreeSet, BinaryHeap};
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use wordle::game::{self, Io, Mode, Rules};
+use wordle::input::{InputError, LineInput};
+use wordle::language::Language;
+use wordle::solver::{information_entrophy, WordEntrophy};
+use wordle::theme::Theme;
+use wordle::timer::{ManualClock, SystemClock};
+use wordle::variant::Variant;
+
+fn words(list: &[&str]) -> BTreeSet<String> {
+    list.iter().map(|x| x.to_string()).collect()
+}
+
+fn rules<'a>(mode: Mode, language: &'a Language, library: &'a BTreeSet<String>,
+             opening: &'a dyn Fn() -> BinaryHeap<WordEntrophy>) -> Rules<'a> {
+    Rules {
+        mode,
+        language,
+        final_words: library,
+        acceptable_words: library,
+        opening,
+        weights: None,
+        difficult: false,
+        ultra: false,
+        explain: false,
+        variant: Variant::Classic,
+        theme: Theme::Default,
+        time_limit: None,
+        guess_time_limit: None,
+        record_times: false,
+    }
+}
+
+/// Lines typed after a delay each, moving a manual clock
+struct SlowTyper<'a> {
+    lines: Vec<&'static str>,
+    clock: &'a ManualClock,
+    delay: Duration,
+}
+
+impl LineInput for SlowTyper<'_> {
+    fn read_line(&mut self, deadline: Option<Instant>) -> Result<String, InputError> {
+        self.clock.advance(self.delay);
+        if self.lines.is_empty() {
+            // nothing more is typed, so the clock runs out
+            return Err(if deadline.is_some() { InputError::TimedOut } else { InputError::Closed });
+        }
+        Ok(self.lines.remove(0).to_string())
+    }
+}
+
+#[test]
+fn test_protocol_round() {
+    let language = Language::english();
+    let library = words(&["cigar", "crane", "rebut", "sissy", "humph"]);
+    let opening = || information_entrophy(&library);
+    let mut rules = rules(Mode::Test, &language, &library, &opening);
+    rules.difficult = true;
+    let mut input = Cursor::new("crane\nhello\nsissy\nhint 2\ncigar\n");
+    let (mut output, mut error) = (Vec::new(), Vec::new());
+    let mut io = Io { input: &mut input, output: &mut output, error: &mut error, clock: &SystemClock };
+    let round = game::play(&rules, &mut io, Some("cigar")).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "GYYRR YXGXRXXXXXXXXRXXXYXXXXXXXX\n\
+         INVALID\n\
+         INVALID\n\
+         HINT 2 2 I\n\
+         GGGGG GXGXRXGXGXXXXRXXXGXXXXXXXX\n\
+         CORRECT 2\n"
+    );
+    // the difficult rule broken is told on the error handle
+    assert_eq!(String::from_utf8(error).unwrap(), "1st letter must be C\n");
+    assert!(round.win);
+    assert_eq!(round.guesses, vec!["CRANE", "CIGAR"]);
+    assert_eq!(round.hints, vec![2]);
+}
+
+#[test]
+fn test_jsonl_round() {
+    let language = Language::english();
+    let library = words(&["cigar", "crane", "rebut", "sissy", "humph"]);
+    let opening = || information_entrophy(&library);
+    let mut rules = rules(Mode::Jsonl, &language, &library, &opening);
+    rules.difficult = true;
+    let mut input = Cursor::new("crane\nsissy\nhumph\n");
+    let (mut output, mut error) = (Vec::new(), Vec::new());
+    let mut io = Io { input: &mut input, output: &mut output, error: &mut error, clock: &SystemClock };
+    let round = game::play(&rules, &mut io, Some("cigar")).unwrap();
+    let events: Vec<serde_json::Value> = String::from_utf8(output).unwrap()
+        .lines()
+        .map(|x| serde_json::from_str(x).unwrap())
+        .collect();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0]["event"], "guess");
+    assert_eq!(events[0]["feedback"], "GYYRR");
+    // the difficult rule broken is part of the event instead of the error handle
+    assert_eq!(events[1]["event"], "invalid");
+    assert_eq!(events[1]["reason"], "1st letter must be C");
+    assert_eq!(events[2]["event"], "invalid");
+    assert_eq!(events[3]["event"], "end");
+    assert_eq!(events[3]["win"], false);
+    assert_eq!(events[3]["attempts"], 1);
+    assert!(error.is_empty());
+    assert!(!round.win);
+}
+
+#[test]
+fn test_interactive_round_with_colors() {
+    console::set_colors_enabled(true);
+    let language = Language::english();
+    let library = words(&["cigar", "crane", "rebut"]);
+    let opening = || information_entrophy(&library);
+    let rules = rules(Mode::Normal, &language, &library, &opening);
+    // the answer is asked for, then one suggestion request and two guesses
+    let mut input = Cursor::new("hello\ncrane\nY\nrebut\nN\ncrane\n");
+    let (mut output, mut error) = (Vec::new(), Vec::new());
+    let mut io = Io { input: &mut input, output: &mut output, error: &mut error, clock: &SystemClock };
+    let round = game::play(&rules, &mut io, None).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("\u{1b}["), "letters are colored");
+    let plain = console::strip_ansi_codes(&output).to_string();
+    assert!(plain.starts_with("Please input the answer here: Invalid answer! Please try again!\n"));
+    assert!(plain.contains("Answer assigned: CRANE :)"));
+    assert!(plain.contains("Possible guesses: 3 in total"));
+    assert!(plain.ends_with("CRANE\nCorrect! :D You tried 2 times.\n"));
+    assert_eq!(round.states, vec![[1, 1, 0, 0, 0], [2; 5]]);
+}
+
+#[test]
+fn test_clock_runs_out() {
+    let language = Language::english();
+    let library = words(&["cigar", "crane", "rebut"]);
+    let opening = || information_entrophy(&library);
+    let mut rules = rules(Mode::Test, &language, &library, &opening);
+    rules.guess_time_limit = Some(10);
+    rules.record_times = true;
+    let clock = ManualClock::new();
+    // the first guess takes 4 seconds, then nothing is typed
+    let mut input = SlowTyper { lines: vec!["rebut"], clock: &clock, delay: Duration::from_secs(4) };
+    let (mut output, mut error) = (Vec::new(), Vec::new());
+    let mut io = Io { input: &mut input, output: &mut output, error: &mut error, clock: &clock };
+    let round = game::play(&rules, &mut io, Some("cigar")).unwrap();
+    assert_eq!(round.times, vec![4000]);
+    assert!(round.timed_out);
+    assert!(String::from_utf8(output).unwrap().ends_with("TIMEOUT\nFAILED CIGAR\n"));
+
+    // a guess typed after 12 seconds is too late
+    let mut input = SlowTyper { lines: vec!["rebut", "crane"], clock: &clock, delay: Duration::from_secs(12) };
+    let mut output = Vec::new();
+    let mut io = Io { input: &mut input, output: &mut output, error: &mut error, clock: &clock };
+    let round = game::play(&rules, &mut io, Some("cigar")).unwrap();
+    assert!(round.guesses.is_empty() && round.timed_out);
+}
+
+#[test]
+fn test_answer_of_day() {
+    let library = words(&["cigar", "crane", "rebut", "sissy", "humph"]);
+    // the same seed always gives the same order of days
+    let first = game::answer_of_day(&library, 1, &mut StdRng::seed_from_u64(7));
+    assert_eq!(first, game::answer_of_day(&library, 1, &mut StdRng::seed_from_u64(7)));
+    let days: BTreeSet<String> = (1..=5).map(|day| game::answer_of_day(&library, day, &mut StdRng::seed_from_u64(7))).collect();
+    assert_eq!(days, library);
+}
@@ -0,0 +1,59 @@
+//! Runs every case in `tests/cases`, so new cases need no test function
+//! The course cases are left to `basic_requirements.rs`, which owns them, so no case runs twice
+//!
+//! A case is `NAME.args` with optional `NAME.in`, `NAME.ans`, `NAME.env` and state files,
+//! any mode of the program can be covered by its arguments and input.
+//!
+//! cargo test --test golden -- [FILTER...] [--update-golden]
+//!
+//! With `--update-golden` (or `UPDATE_GOLDEN=1`) the answers are rewritten from the current program,
+//! review the diff before committing.
+
+mod common;
+use common::TestCase;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Cases named here belong to the course tests
+const COURSE_TESTS: &str = include_str!("basic_requirements.rs");
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let update = args.iter().any(|x| x == "--update-golden") || std::env::var_os("UPDATE_GOLDEN").is_some();
+    let filters: Vec<&String> = args.iter().filter(|x| !x.starts_with('-')).collect();
+    let names: Vec<String> = TestCase::discover()
+        .into_iter()
+        .filter(|name| !COURSE_TESTS.contains(&format!("\"{}\"", name)))
+        .filter(|name| filters.is_empty() || filters.iter().any(|f| name.contains(f.as_str())))
+        .collect();
+
+    println!("\nrunning {} golden cases", names.len());
+    let mut failed: Vec<String> = Vec::new();
+    for name in &names {
+        let mut case = TestCase::read(name);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            if update {
+                case.update_golden();
+            } else {
+                case.run_golden();
+            }
+        }));
+        match result {
+            Ok(()) => println!("case {} ... ok", case.name()),
+            Err(_) => {
+                println!("case {} ... FAILED", case.name());
+                failed.push(name.clone());
+            }
+        }
+    }
+
+    println!(
+        "\ngolden result: {}. {} passed; {} failed",
+        if failed.is_empty() { "ok" } else { "FAILED" },
+        names.len() - failed.len(),
+        failed.len()
+    );
+    if !failed.is_empty() {
+        println!("failed cases: {}", failed.join(" "));
+        std::process::exit(1);
+    }
+}
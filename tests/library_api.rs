@@ -0,0 +1,189 @@
+use std::collections::BTreeSet;
+use wordle::feedback::{compute_state, explain, Reason};
+use wordle::hard_mode::{HardMode, HardModeViolation};
+use wordle::openers::{opener_stats, pattern_buckets, rank_openers, RankBy};
+use wordle::query::{rank, Query, SortBy};
+use wordle::solver::{answer_weights, filter_words, guess_entrophy, information_entrophy, play_out, play_out_all, weighted_guess_entrophy, weighted_guess_score, weighted_guess_scores};
+use wordle::variant::Variant;
+
+#[test]
+fn test_explain_repeated_letters() {
+    let result = explain("lolly", "hello");
+    let reasons: Vec<Reason> = result.iter().map(|x| x.reason).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            Reason::Exhausted { in_answer: 2 },
+            Reason::Present { in_answer: 1, taken: 0 },
+            Reason::Exact,
+            Reason::Exact,
+            Reason::Absent,
+        ]
+    );
+    assert_eq!(compute_state("lolly", "hello"), [0, 1, 2, 2, 0]);
+    // structured data can be serialized for other programs
+    let json = serde_json::to_value(&result[1]).unwrap();
+    assert_eq!(json["reason"]["kind"], "present");
+    assert_eq!(json["state"], 1);
+}
+
+#[test]
+fn test_scoring() {
+    assert_eq!(compute_state("crane", "crane"), [2; 5]);
+    // only the first of two copies is yellow when the answer has one
+    assert_eq!(compute_state("speed", "abide"), [0, 0, 1, 0, 1]);
+    // the green takes its copy before the yellows from left to right
+    assert_eq!(compute_state("eerie", "there"), [1, 0, 1, 0, 2]);
+}
+
+#[test]
+fn test_hard_mode() {
+    let state = compute_state("crane", "cigar");
+    let mut hard = HardMode::new(false);
+    hard.update("crane", &state);
+    assert_eq!(hard.check("sugar"), Err(HardModeViolation::GreenMoved { position: 0, letter: 'c' }));
+    assert_eq!(hard.check("cloud"), Err(HardModeViolation::MissingLetter { letter: 'a', count: 1 }));
+    assert_eq!(hard.check("carol"), Ok(()));
+    // grey letters and known wrong positions only count in ultra mode
+    assert_eq!(hard.check("craft"), Ok(()));
+    let mut ultra = HardMode::new(true);
+    ultra.update("crane", &state);
+    assert_eq!(ultra.check("craft"), Err(HardModeViolation::WrongPosition { position: 1, letter: 'r' }));
+    assert_eq!(ultra.check("cared"), Err(HardModeViolation::ExtraLetter { letter: 'e', count: 0 }));
+}
+
+#[test]
+fn test_entrophy() {
+    let library: BTreeSet<String> = ["abcde", "abcdf", "xyzuv", "xyzuw"].iter().map(|x| x.to_string()).collect();
+    // abcde splits the library into 1, 1 and 2 words
+    assert_eq!(guess_entrophy("abcde", &library), 1.5);
+    // a guess matching no word tells nothing
+    assert_eq!(guess_entrophy("ghijk", &library), 0.0);
+    let ranking = information_entrophy(&library);
+    assert_eq!(ranking.peek().unwrap().0, "abcde");
+    let left = filter_words(&library, "abcde", &[2, 2, 2, 2, 0]);
+    assert_eq!(left.into_iter().collect::<Vec<String>>(), vec!["abcdf".to_string()]);
+}
+
+#[test]
+fn test_query() {
+    let library: BTreeSet<String> = ["bread", "dream", "great", "press", "eerie"].iter().map(|x| x.to_string()).collect();
+    let query = Query::parse(Some("_R_A_"), "e", "s", &[]).unwrap();
+    let found = query.filter(&library);
+    assert_eq!(found.iter().collect::<Vec<_>>(), vec!["bread", "dream", "great"]);
+    // yellow letters are in the word but not at their position
+    let misplaced = Query::parse(None, "", "", &["d____".to_string()]).unwrap();
+    assert_eq!(misplaced.filter(&library).iter().collect::<Vec<_>>(), vec!["bread"]);
+    // included letters count their copies
+    let twice = Query::parse(None, "ee", "", &[]).unwrap();
+    assert_eq!(twice.filter(&library).iter().collect::<Vec<_>>(), vec!["eerie"]);
+    assert!(Query::parse(Some("_S___"), "", "s", &[]).is_err());
+    assert!(Query::parse(Some("_R_A"), "", "", &[]).is_err());
+    let ranked = rank(&found, SortBy::Letters, None);
+    assert_eq!(ranked[0].0, "bread");
+    let frequency = [("great".to_string(), 30.0), ("dream".to_string(), 10.0)].into_iter().collect();
+    let ranked = rank(&found, SortBy::Frequency, Some(&frequency));
+    assert_eq!(ranked.iter().map(|x| x.0.as_str()).collect::<Vec<_>>(), vec!["great", "dream", "bread"]);
+    // the query is a set of guesses with their colors for the solver's filter
+    assert_eq!(query.constraints(), vec![("_r_a_".to_string(), [0, 2, 0, 2, 0]), ("s____".to_string(), [0; 5])]);
+}
+
+#[test]
+fn test_compute_state_agrees_with_explain() {
+    let words = ["lolly", "hello", "eerie", "speed", "abbey", "babes", "crane", "nacre"];
+    for guess in words {
+        for answer in words {
+            let mut expected = [0; 5];
+            for x in explain(guess, answer) {
+                expected[x.position] = x.state;
+            }
+            assert_eq!(compute_state(guess, answer), expected, "{} against {}", guess, answer);
+        }
+    }
+}
+
+#[test]
+fn test_openers() {
+    let answers: BTreeSet<String> = ["light", "might", "sight", "crane"].iter().map(|x| x.to_string()).collect();
+    // night leaves light, might and sight in one bucket
+    let stats = opener_stats("night", &answers);
+    assert_eq!(stats.worst, 3);
+    assert_eq!(stats.patterns, 2);
+    assert_eq!(stats.expected, 2.5);
+    // the same entrophy the solver ranks its guesses by
+    assert_eq!(stats.entropy, guess_entrophy("night", &answers));
+    assert_eq!(pattern_buckets("night", &answers)[&[0, 2, 2, 2, 2]].len(), 3);
+    // mails splits every answer apart
+    let guesses: BTreeSet<String> = ["night", "mails"].iter().map(|x| x.to_string()).collect();
+    let ranked = rank_openers(&guesses, &answers, RankBy::Worst);
+    assert_eq!(ranked[0].word, "mails");
+    assert_eq!(ranked[0].entropy, 2.0);
+}
+
+#[test]
+fn test_play_out_all() {
+    let acceptable_words: BTreeSet<String> = ["light", "might", "sight", "tight", "fight", "night", "right", "crane", "molts", "abide"]
+        .iter().map(|x| x.to_string()).collect();
+    let answers: BTreeSet<String> = ["light", "might", "sight", "tight", "fight", "night", "right", "crane", "azure"]
+        .iter().map(|x| x.to_string()).collect();
+    let games = play_out_all(&answers, &acceptable_words, "crane");
+    assert_eq!(games.len(), answers.len());
+    // the same games as playing each answer on its own
+    for answer in &answers {
+        assert_eq!(games[answer], play_out(answer, &acceptable_words, "crane"), "{}", answer);
+    }
+    assert_eq!(games["crane"], Some(1));
+    // azure is not acceptable, the solver never finds it
+    assert_eq!(games["azure"], None);
+}
+
+#[test]
+fn test_weighted_entrophy() {
+    let final_words: BTreeSet<String> = ["light", "might"].iter().map(|x| x.to_string()).collect();
+    let acceptable_words: BTreeSet<String> = ["light", "might", "sight", "tight", "molts"].iter().map(|x| x.to_string()).collect();
+    let weights = answer_weights(&final_words, &acceptable_words, 0.0, None);
+    assert_eq!(weights.len(), 2);
+    // only light and might can be the answer, both molts and might split them
+    assert_eq!(weighted_guess_entrophy("molts", &acceptable_words, &weights), 1.0);
+    assert_eq!(weighted_guess_entrophy("might", &acceptable_words, &weights), 1.0);
+    // but might may win right away, which only the score counts
+    assert_eq!(weighted_guess_score("molts", &acceptable_words, &weights), 1.0);
+    assert_eq!(weighted_guess_score("might", &acceptable_words, &weights), 1.5);
+    assert_eq!(weighted_guess_scores(&acceptable_words, &weights).peek().unwrap().0, "light");
+    // a frequency table scales the weights, missing words take the least frequency
+    let frequency = [("light".to_string(), 30.0), ("sight".to_string(), 10.0)].into_iter().collect();
+    let weights = answer_weights(&final_words, &acceptable_words, 0.5, Some(&frequency));
+    assert_eq!(weights["light"], 1.0);
+    assert_eq!(weights["might"], 1.0 / 3.0);
+    assert_eq!(weights["sight"], 0.5 / 3.0);
+}
+
+#[test]
+fn test_variant_feedback() {
+    let state = compute_state("slate", "tight");
+    assert_eq!(state, [0, 0, 0, 1, 0]);
+    assert_eq!(Variant::Classic.feedback("slate", "tight", state), state);
+    assert_eq!(Variant::HiddenKeyboard.feedback("slate", "tight", state), state);
+    assert_eq!(Variant::NoYellows.feedback("slate", "tight", state), [0; 5]);
+    assert_eq!(Variant::Counts.feedback("slate", "tight", state), [1, 0, 0, 0, 0]);
+    // exactly one tile lies, the same one every time
+    for guess in ["slate", "crane", "fight", "night"] {
+        let state = compute_state(guess, "tight");
+        let shown = Variant::Lying.feedback(guess, "tight", state);
+        assert_eq!(state.iter().zip(shown).filter(|(a, b)| **a != *b).count(), 1);
+        assert_eq!(Variant::Lying.feedback(guess, "tight", state), shown);
+    }
+    // a wrong guess with four greens never shows as a win
+    let state = compute_state("azide", "abide");
+    assert_eq!(state, [2, 0, 2, 2, 2]);
+    assert_ne!(Variant::Lying.feedback("azide", "abide", state), [2; 5]);
+    for answer in ["fight", "light", "might", "night", "right", "sight"] {
+        let state = compute_state("tight", answer);
+        let shown = Variant::Lying.feedback("tight", answer, state);
+        assert_ne!(shown, [2; 5]);
+        assert_eq!(state.iter().zip(shown).filter(|(a, b)| **a != *b).count(), 1);
+    }
+    // the winning row is never changed
+    assert_eq!(Variant::Lying.feedback("tight", "tight", [2; 5]), [2; 5]);
+    assert_eq!(Variant::Counts.feedback("tight", "tight", [2; 5]), [2; 5]);
+}